@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use rand::seq::SliceRandom;
+use rand_chacha::{
+    rand_core::{RngCore, SeedableRng},
+    ChaCha8Rng,
+};
+
+const ALGAE_MAX: usize = 24;
+const ALGAE_GROWTH_INTERVAL_SECONDS: f32 = 2.;
+const ALGAE_NUTRIENTS_PER_PATCH: f32 = 8.;
+const ALGAE_NUTRIENTS_PER_GROWTH: f32 = 0.5;
+const ALGAE_GROWTH_RATE: f32 = 0.1;
+const ALGAE_DIEBACK_RATE: f32 = 0.01;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, setup_algae_system)
+        .add_systems(
+            Update,
+            grow_algae_system.run_if(on_timer(Duration::from_secs_f32(
+                ALGAE_GROWTH_INTERVAL_SECONDS,
+            ))),
+        )
+        .init_resource::<Nutrients>();
+}
+
+/// Nutrients dissolved in the water, released by decomposing remains and consumed by algae.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Nutrients(f32);
+
+#[derive(Component)]
+pub struct Algae {
+    growth: f32,
+    size: Vec3,
+}
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct AlgaeRng(ChaCha8Rng);
+
+#[derive(Resource, Deref)]
+pub struct AlgaeMesh(Handle<Mesh>);
+
+#[derive(Resource, Deref)]
+pub struct AlgaeMaterials(Vec<Handle<StandardMaterial>>);
+
+fn setup_algae_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Sphere::new(0.5));
+    commands.insert_resource(AlgaeMesh(mesh));
+
+    let mut seeded_rng = ChaCha8Rng::seed_from_u64(19878367467712);
+    let algae_materials = (0..8)
+        .map(|_| {
+            let base_color = Color::hsl((seeded_rng.next_u32() % 40 + 90) as f32, 0.5, 0.3);
+
+            materials.add(StandardMaterial {
+                base_color,
+                perceptual_roughness: 1.,
+                ..default()
+            })
+        })
+        .collect();
+    commands.insert_resource(AlgaeMaterials(algae_materials));
+    commands.insert_resource(AlgaeRng(seeded_rng));
+}
+
+fn grow_algae_system(
+    mut commands: Commands,
+    mut nutrients: ResMut<Nutrients>,
+    mut algae_query: Query<(Entity, &mut Algae, &mut Transform)>,
+    mut algae_rng: ResMut<AlgaeRng>,
+    algae_mesh: Res<AlgaeMesh>,
+    algae_materials: Res<AlgaeMaterials>,
+) {
+    let mut algae_count = 0;
+
+    for (entity, mut algae, mut transform) in algae_query.iter_mut() {
+        algae_count += 1;
+
+        if **nutrients >= ALGAE_NUTRIENTS_PER_GROWTH && algae.growth < 1. {
+            **nutrients -= ALGAE_NUTRIENTS_PER_GROWTH;
+            algae.growth = (algae.growth + ALGAE_GROWTH_RATE).min(1.);
+        } else {
+            algae.growth -= ALGAE_DIEBACK_RATE;
+            if algae.growth <= 0. {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        }
+
+        transform.scale = algae.size * algae.growth;
+    }
+
+    if **nutrients >= ALGAE_NUTRIENTS_PER_PATCH && algae_count < ALGAE_MAX {
+        **nutrients -= ALGAE_NUTRIENTS_PER_PATCH;
+
+        let x = unit(&mut algae_rng) * 3.2 - 1.6;
+        let (translation, size) = if algae_rng.next_u32() % 2 == 0 {
+            (
+                Vec3::new(x, -1.72, unit(&mut algae_rng) * 0.7 - 0.35),
+                Vec3::new(0.12, 0.03, 0.12),
+            )
+        } else {
+            (
+                Vec3::new(x, unit(&mut algae_rng) * 2.6 - 1.6, -0.43),
+                Vec3::new(0.15, 0.15, 0.02),
+            )
+        };
+
+        commands.spawn((
+            Algae { growth: 0.1, size },
+            Transform::from_translation(translation).with_scale(size * 0.1),
+            Mesh3d(algae_mesh.clone()),
+            MeshMaterial3d(algae_materials.choose(&mut algae_rng.0).unwrap().clone()),
+        ));
+    }
+}
+
+fn unit(rng: &mut AlgaeRng) -> f32 {
+    rng.next_u32() as f32 / u32::MAX as f32
+}
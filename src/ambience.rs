@@ -0,0 +1,249 @@
+use std::time::{Duration, Instant};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use serde::Deserialize;
+
+use crate::{bubbles::BubblerRate, config::config_section, toast::ToastEvent};
+
+const LOAD_SAMPLE_INTERVAL_SECONDS: f32 = 2.;
+const LOAD_SMOOTHING: f32 = 0.5;
+const LOAD_ACTIVITY_BOOST: f32 = 1.;
+const LOAD_BUBBLER_BOOST: f32 = 2.;
+const LOAD_FOG_DENSITY: f32 = 0.12;
+const FOG_COLOR_CALM: Vec3 = Vec3::new(0.1, 0.3, 0.5);
+const FOG_COLOR_BUSY: Vec3 = Vec3::new(0.6, 0.2, 0.1);
+
+pub(super) fn plugin(app: &mut App) {
+    let config: AmbienceConfig = config_section(app, "ambience");
+
+    app.add_systems(
+        Update,
+        (
+            sample_load_system.run_if(on_timer(Duration::from_secs_f32(
+                LOAD_SAMPLE_INTERVAL_SECONDS,
+            ))),
+            load_ambience_system.run_if(resource_changed::<SystemLoad>),
+        )
+            .chain()
+            .run_if(|load: Res<SystemLoad>| load.config.mode != AmbienceMode::Off),
+    )
+    .insert_resource(SystemLoad {
+        config,
+        ..default()
+    });
+}
+
+/// Which reading of the machine the tank follows.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+enum AmbienceMode {
+    #[default]
+    Off,
+    Cpu,
+    Memory,
+    Network,
+    /// Whichever of the others is busiest.
+    All,
+}
+
+/// The readings at which the tank is at its calmest and at its busiest.
+#[derive(Deserialize, Clone, Copy)]
+struct Thresholds {
+    calm: f32,
+    busy: f32,
+}
+
+impl Thresholds {
+    fn normalize(&self, value: f32) -> f32 {
+        if self.busy <= self.calm {
+            return if value >= self.busy { 1. } else { 0. };
+        }
+
+        ((value - self.calm) / (self.busy - self.calm)).clamp(0., 1.)
+    }
+}
+
+/// The `[ambience]` table of the config file, with cpu and memory in percent and network in
+/// megabytes per second.
+#[derive(Deserialize)]
+#[serde(default)]
+struct AmbienceConfig {
+    mode: AmbienceMode,
+    cpu: Thresholds,
+    memory: Thresholds,
+    network: Thresholds,
+}
+
+impl Default for AmbienceConfig {
+    fn default() -> Self {
+        Self {
+            mode: AmbienceMode::Off,
+            cpu: Thresholds {
+                calm: 10.,
+                busy: 90.,
+            },
+            memory: Thresholds {
+                calm: 50.,
+                busy: 95.,
+            },
+            network: Thresholds {
+                calm: 0.,
+                busy: 10.,
+            },
+        }
+    }
+}
+
+/// How hard the machine is working, read from `/proc`.
+#[derive(Resource, Default)]
+pub struct SystemLoad {
+    config: AmbienceConfig,
+    pub cpu: f32,
+    pub memory: f32,
+    pub network: f32,
+    /// How busy the tank should look, from zero to one.
+    pub intensity: f32,
+}
+
+impl SystemLoad {
+    /// Multiplier on how quickly the fish swim, who get restless on a busy machine.
+    pub fn activity(&self) -> f32 {
+        1. + self.intensity * LOAD_ACTIVITY_BOOST
+    }
+
+    fn target_intensity(&self) -> f32 {
+        let config = &self.config;
+        let cpu = config.cpu.normalize(self.cpu);
+        let memory = config.memory.normalize(self.memory);
+        let network = config.network.normalize(self.network);
+
+        match config.mode {
+            AmbienceMode::Off => 0.,
+            AmbienceMode::Cpu => cpu,
+            AmbienceMode::Memory => memory,
+            AmbienceMode::Network => network,
+            AmbienceMode::All => cpu.max(memory).max(network),
+        }
+    }
+}
+
+/// Running totals from `/proc`, which only mean anything compared to an earlier reading.
+struct ProcCounters {
+    at: Instant,
+    cpu_total: u64,
+    cpu_idle: u64,
+    network_bytes: u64,
+}
+
+impl ProcCounters {
+    fn read() -> Option<Self> {
+        let stat = std::fs::read_to_string("/proc/stat").ok()?;
+        let cpu: Vec<u64> = stat
+            .lines()
+            .next()?
+            .split_whitespace()
+            .skip(1)
+            .filter_map(|field| field.parse().ok())
+            .collect();
+
+        let network = std::fs::read_to_string("/proc/net/dev").ok()?;
+        let network_bytes = network
+            .lines()
+            .skip(2)
+            .filter_map(|line| line.split_once(':'))
+            .filter(|(interface, _)| interface.trim() != "lo")
+            .map(|(_, counters)| {
+                let counters: Vec<u64> = counters
+                    .split_whitespace()
+                    .filter_map(|field| field.parse().ok())
+                    .collect();
+                counters.first().unwrap_or(&0) + counters.get(8).unwrap_or(&0)
+            })
+            .sum();
+
+        Some(Self {
+            at: Instant::now(),
+            cpu_total: cpu.iter().sum(),
+            // idle plus waiting on io.
+            cpu_idle: cpu.get(3)? + cpu.get(4).unwrap_or(&0),
+            network_bytes,
+        })
+    }
+}
+
+/// Percentage of memory in use, going by what the kernel reckons is available.
+fn read_memory() -> Option<f32> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let field = |name: &str| -> Option<f32> {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name))?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    };
+
+    let total = field("MemTotal:")?;
+    let available = field("MemAvailable:")?;
+
+    Some((1. - available / total) * 100.)
+}
+
+fn sample_load_system(
+    mut load: ResMut<SystemLoad>,
+    mut previous: Local<Option<ProcCounters>>,
+    mut warned: Local<bool>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    let (Some(counters), Some(memory)) = (ProcCounters::read(), read_memory()) else {
+        if !*warned {
+            *warned = true;
+            toast_events.write(ToastEvent(
+                "ambience: could not read system load from /proc".into(),
+            ));
+        }
+        return;
+    };
+
+    if let Some(previous) = previous.as_ref() {
+        let total = counters.cpu_total.saturating_sub(previous.cpu_total);
+        let idle = counters.cpu_idle.saturating_sub(previous.cpu_idle);
+        if total > 0 {
+            load.cpu = (1. - idle as f32 / total as f32) * 100.;
+        }
+
+        let seconds = counters.at.duration_since(previous.at).as_secs_f32();
+        if seconds > 0. {
+            let bytes = counters
+                .network_bytes
+                .saturating_sub(previous.network_bytes);
+            load.network = bytes as f32 / seconds / 1_000_000.;
+        }
+    }
+
+    load.memory = memory;
+
+    let target = load.target_intensity();
+    load.intensity += (target - load.intensity) * LOAD_SMOOTHING;
+
+    *previous = Some(counters);
+}
+
+fn load_ambience_system(
+    mut commands: Commands,
+    load: Res<SystemLoad>,
+    mut bubbler_rate: ResMut<BubblerRate>,
+    camera: Single<Entity, With<Camera3d>>,
+) {
+    **bubbler_rate = 1. + load.intensity * LOAD_BUBBLER_BOOST;
+
+    let color = FOG_COLOR_CALM.lerp(FOG_COLOR_BUSY, load.intensity);
+    commands.entity(*camera).insert(DistanceFog {
+        color: Color::srgb(color.x, color.y, color.z),
+        falloff: FogFalloff::Exponential {
+            density: load.intensity * LOAD_FOG_DENSITY,
+        },
+        ..default()
+    });
+}
@@ -0,0 +1,449 @@
+use std::f32::consts::TAU;
+
+use bevy::{
+    audio::{AddAudioSource, AudioSinkPlayback, Decodable, Source, SpatialScale, Volume},
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::{
+    camera::Daytime,
+    config::config_section,
+    creatures::{lifecycle::FishSkeleton, population::FishBirthEvent},
+    pellets::PelletBiteEvent,
+    toast::ToastEvent,
+    Flags,
+};
+
+const SAMPLE_RATE: u32 = 44_100;
+/// Distance between the listener's ears, the width of the tank so that sounds pan fully.
+const EAR_GAP: f32 = 3.6;
+/// Shrinks the tank for the sake of falloff, so that nothing inside it sounds far away.
+const SPATIAL_SCALE: f32 = 0.3;
+const VOLUME_STEP: f32 = 0.1;
+const AMBIENCE_FADE_RATE: f32 = 0.5;
+const NIGHT_AMBIENCE_LEVEL: f32 = 0.6;
+
+pub(super) fn plugin(app: &mut App) {
+    let config: AudioConfig = config_section(app, "audio");
+
+    app.add_audio_source::<Hum>()
+        .add_audio_source::<Tone>()
+        .add_systems(Startup, setup_audio_system)
+        .add_systems(
+            Update,
+            (
+                change_volume_system,
+                eating_sounds_system,
+                birth_sounds_system,
+                death_sounds_system,
+                play_sfx_system,
+                ambience_volume_system,
+            )
+                .chain(),
+        )
+        .insert_resource(AudioLevels {
+            master: config.volume.clamp(0., 1.),
+            config,
+        })
+        .add_event::<SfxEvent>()
+        .add_event::<VolumeEvent>();
+}
+
+/// The `[audio]` table of the config file, with each volume from zero to one.
+#[derive(Deserialize)]
+#[serde(default)]
+struct AudioConfig {
+    volume: f32,
+    ambience: f32,
+    /// Clicks of the light switch and pellets being dropped in.
+    effects: f32,
+    eating: f32,
+    births: f32,
+    deaths: f32,
+    bubbles: f32,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            volume: 1.,
+            ambience: 0.5,
+            effects: 1.,
+            eating: 0.6,
+            births: 1.,
+            deaths: 1.,
+            bubbles: 0.5,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct AudioLevels {
+    config: AudioConfig,
+    master: f32,
+}
+
+#[derive(Clone, Copy)]
+pub enum Sfx {
+    ClickOn,
+    ClickOff,
+    Pellet,
+    Eat,
+    Birth,
+    Death,
+    Bubble,
+}
+
+impl Sfx {
+    fn volume(self, levels: &AudioLevels) -> f32 {
+        let config = &levels.config;
+        let category = match self {
+            Self::ClickOn | Self::ClickOff | Self::Pellet => config.effects,
+            Self::Eat => config.eating,
+            Self::Birth => config.births,
+            Self::Death => config.deaths,
+            Self::Bubble => config.bubbles,
+        };
+
+        levels.master * category.clamp(0., 1.)
+    }
+
+    /// The recorded sounds go a long way when played back faster or slower.
+    fn speed(self) -> f32 {
+        match self {
+            Self::ClickOn | Self::ClickOff | Self::Pellet | Self::Birth | Self::Death => 1.,
+            Self::Eat => 2.2,
+            Self::Bubble => 1.4,
+        }
+    }
+}
+
+/// Plays a sound effect, panned to the given x position in the tank if there is one.
+#[derive(Event)]
+pub struct SfxEvent(pub Sfx, pub Option<f32>);
+
+#[derive(Event)]
+pub enum VolumeEvent {
+    Up,
+    Down,
+}
+
+#[derive(Resource)]
+struct SfxSounds {
+    on: Handle<AudioSource>,
+    off: Handle<AudioSource>,
+    bubble: Handle<AudioSource>,
+    birth: Handle<Tone>,
+    death: Handle<Tone>,
+}
+
+/// A looping drone of filter hum and rushing water, synthesized rather than recorded.
+#[derive(Asset, TypePath, Clone, Copy)]
+struct Hum {
+    frequency: f32,
+    /// How much of the mix is water rather than motor.
+    rush: f32,
+}
+
+impl Decodable for Hum {
+    type DecoderItem = f32;
+    type Decoder = HumDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        HumDecoder {
+            hum: *self,
+            phase: 0.,
+            noise: 0.,
+            seed: 0x9E37_79B9,
+        }
+    }
+}
+
+struct HumDecoder {
+    hum: Hum,
+    phase: f32,
+    noise: f32,
+    seed: u32,
+}
+
+impl Iterator for HumDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.phase = (self.phase + self.hum.frequency / SAMPLE_RATE as f32) % 1.;
+        let tone = (self.phase * TAU).sin() * 0.5 + (self.phase * 2. * TAU).sin() * 0.2;
+
+        // xorshift white noise, smoothed down into a low rush.
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        let white = self.seed as f32 / u32::MAX as f32 * 2. - 1.;
+        self.noise += (white - self.noise) * 0.02;
+
+        Some(tone * (1. - self.hum.rush) * 0.3 + self.noise * self.hum.rush * 4.)
+    }
+}
+
+impl Source for HumDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        None
+    }
+}
+
+/// A short synthesized note that slides from one pitch to another as it dies away.
+#[derive(Asset, TypePath, Clone, Copy)]
+struct Tone {
+    from: f32,
+    to: f32,
+    seconds: f32,
+    /// How much of the bell-like overtone rings above the note.
+    shimmer: f32,
+}
+
+impl Decodable for Tone {
+    type DecoderItem = f32;
+    type Decoder = ToneDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        ToneDecoder {
+            tone: *self,
+            sample: 0,
+            phase: 0.,
+            overtone_phase: 0.,
+        }
+    }
+}
+
+struct ToneDecoder {
+    tone: Tone,
+    sample: u32,
+    phase: f32,
+    overtone_phase: f32,
+}
+
+impl Iterator for ToneDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let seconds = self.sample as f32 / SAMPLE_RATE as f32;
+        let progress = seconds / self.tone.seconds;
+        if progress >= 1. {
+            return None;
+        }
+        self.sample += 1;
+
+        // the pitch glides evenly in musical terms rather than in hertz.
+        let frequency = self.tone.from * (self.tone.to / self.tone.from).powf(progress);
+        self.phase = (self.phase + frequency / SAMPLE_RATE as f32) % 1.;
+        self.overtone_phase = (self.overtone_phase + frequency * 2.76 / SAMPLE_RATE as f32) % 1.;
+
+        let attack = (seconds / 0.005).min(1.);
+        let envelope = attack * (1. - progress).powi(3);
+        let note = (self.phase * TAU).sin() + (self.overtone_phase * TAU).sin() * self.tone.shimmer;
+
+        Some(note * envelope * 0.5 / (1. + self.tone.shimmer))
+    }
+}
+
+impl Source for ToneDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<std::time::Duration> {
+        Some(std::time::Duration::from_secs_f32(self.tone.seconds))
+    }
+}
+
+/// One of the two ambient loops, faded in or out as day turns to night.
+#[derive(Component)]
+struct Ambience {
+    daytime: bool,
+    level: f32,
+}
+
+fn setup_audio_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut hums: ResMut<Assets<Hum>>,
+    mut tones: ResMut<Assets<Tone>>,
+) {
+    commands.insert_resource(SfxSounds {
+        on: asset_server.load("embedded://lifecycler/../assets/on.ogg"),
+        off: asset_server.load("embedded://lifecycler/../assets/off.ogg"),
+        bubble: asset_server.load("embedded://lifecycler/../assets/bubble.ogg"),
+        // a bright rising chime for a birth, and a low falling note for a death.
+        birth: tones.add(Tone {
+            from: 1046.,
+            to: 1568.,
+            seconds: 0.7,
+            shimmer: 0.4,
+        }),
+        death: tones.add(Tone {
+            from: 196.,
+            to: 82.,
+            seconds: 1.4,
+            shimmer: 0.,
+        }),
+    });
+
+    commands.spawn((SpatialListener::new(EAR_GAP), Transform::default()));
+
+    for (daytime, hum) in [
+        (
+            true,
+            Hum {
+                frequency: 120.,
+                rush: 0.6,
+            },
+        ),
+        (
+            false,
+            Hum {
+                frequency: 60.,
+                rush: 0.85,
+            },
+        ),
+    ] {
+        commands.spawn((
+            Ambience { daytime, level: 0. },
+            AudioPlayer(hums.add(hum)),
+            PlaybackSettings::LOOP.with_volume(Volume::Linear(0.)),
+        ));
+    }
+}
+
+fn change_volume_system(
+    mut volume_events: EventReader<VolumeEvent>,
+    mut levels: ResMut<AudioLevels>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for event in volume_events.read() {
+        let step = match event {
+            VolumeEvent::Up => VOLUME_STEP,
+            VolumeEvent::Down => -VOLUME_STEP,
+        };
+
+        levels.master = (levels.master + step).clamp(0., 1.);
+        toast_events.write(ToastEvent(format!("volume {:.0}%", levels.master * 100.)));
+    }
+}
+
+fn eating_sounds_system(
+    mut bite_events: EventReader<PelletBiteEvent>,
+    pellets: Query<&Transform>,
+    mut sfx_events: EventWriter<SfxEvent>,
+) {
+    for pellet in bite_events.read() {
+        let x = pellets
+            .get(**pellet)
+            .ok()
+            .map(|transform| transform.translation.x);
+        sfx_events.write(SfxEvent(Sfx::Eat, x));
+    }
+}
+
+fn birth_sounds_system(
+    mut birth_events: EventReader<FishBirthEvent>,
+    mut sfx_events: EventWriter<SfxEvent>,
+) {
+    for birthplace in birth_events.read() {
+        sfx_events.write(SfxEvent(Sfx::Birth, Some(birthplace.x)));
+    }
+}
+
+fn death_sounds_system(
+    skeletons: Query<&Transform, Added<FishSkeleton>>,
+    mut sfx_events: EventWriter<SfxEvent>,
+) {
+    for transform in skeletons.iter() {
+        sfx_events.write(SfxEvent(Sfx::Death, Some(transform.translation.x)));
+    }
+}
+
+fn play_sfx_system(
+    mut commands: Commands,
+    mut sfx_events: EventReader<SfxEvent>,
+    sounds: Res<SfxSounds>,
+    levels: Res<AudioLevels>,
+    flags: Res<Flags>,
+) {
+    for SfxEvent(sfx, x) in sfx_events.read() {
+        let volume = sfx.volume(&levels);
+        if flags.muted || volume <= 0. {
+            continue;
+        }
+
+        let settings = PlaybackSettings::DESPAWN
+            .with_volume(Volume::Linear(volume))
+            .with_speed(sfx.speed());
+        let mut sound = match sfx {
+            Sfx::ClickOn => commands.spawn(AudioPlayer::new(sounds.on.clone())),
+            Sfx::ClickOff => commands.spawn(AudioPlayer::new(sounds.off.clone())),
+            Sfx::Pellet | Sfx::Eat | Sfx::Bubble => {
+                commands.spawn(AudioPlayer::new(sounds.bubble.clone()))
+            }
+            Sfx::Birth => commands.spawn(AudioPlayer(sounds.birth.clone())),
+            Sfx::Death => commands.spawn(AudioPlayer(sounds.death.clone())),
+        };
+
+        match x {
+            Some(x) => sound.insert((
+                PlaybackSettings {
+                    spatial: true,
+                    spatial_scale: Some(SpatialScale::new(SPATIAL_SCALE)),
+                    ..settings
+                },
+                Transform::from_xyz(*x, 0., 0.),
+            )),
+            None => sound.insert(settings),
+        };
+    }
+}
+
+fn ambience_volume_system(
+    time: Res<Time<Real>>,
+    daytime: Res<Daytime>,
+    levels: Res<AudioLevels>,
+    flags: Res<Flags>,
+    mut loops: Query<(&mut Ambience, &mut AudioSink)>,
+) {
+    for (mut ambience, mut sink) in loops.iter_mut() {
+        let target = match (ambience.daytime, **daytime) {
+            (true, true) => 1.,
+            (false, false) => NIGHT_AMBIENCE_LEVEL,
+            _ => 0.,
+        };
+
+        let step = AMBIENCE_FADE_RATE * time.delta_secs();
+        ambience.level += (target - ambience.level).clamp(-step, step);
+
+        let volume = if flags.muted {
+            0.
+        } else {
+            ambience.level * levels.master * levels.config.ambience.clamp(0., 1.)
+        };
+        sink.set_volume(Volume::Linear(volume));
+    }
+}
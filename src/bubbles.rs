@@ -1,97 +1,294 @@
-use std::time::Duration;
+use bevy::prelude::*;
+use serde::Deserialize;
 
-use bevy::{prelude::*, time::common_conditions::on_timer};
-use bevy_hanabi::{
-    Attribute, ColorOverLifetimeModifier, EffectAsset, ExprWriter, Gradient, ParticleEffect,
-    SetAttributeModifier, SizeOverLifetimeModifier, SpawnerSettings, WriterExpr,
+use crate::{
+    audio::{Sfx, SfxEvent},
+    config::config_section,
+    filter::Current,
+    simulation::Interpolated,
+    toast::ToastEvent,
+    water::WATER_SURFACE_HEIGHT,
 };
 
-const BUBBLE_INTERVAL_SECONDS: f32 = 5.;
+const BUBBLE_STONES_MAX: usize = 4;
+const BUBBLE_STONE_HEIGHT: f32 = -1.72;
+/// Stones placed closer than this to an existing one take it away instead.
+const BUBBLE_STONE_REMOVE_DISTANCE: f32 = 0.2;
+const STONE_BUBBLES_PER_SECOND: f32 = 2.;
+const BUBBLE_START_SPEED: f32 = 0.15;
+const BUBBLE_BUOYANCY: f32 = 0.25;
+const BUBBLE_MAX_SPEED: f32 = 0.9;
+const BUBBLE_WOBBLE_FREQUENCY: f32 = 7.;
+const BUBBLE_WOBBLE_AMPLITUDE: f32 = 0.04;
+const SPLASH_DROPLETS: u32 = 5;
+const SPLASH_SECONDS: f32 = 0.4;
+const SPLASH_GRAVITY: f32 = 2.;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Startup, bubbles_setup_system).add_systems(
-        Update,
-        gravel_bubbles_mover.run_if(on_timer(Duration::from_secs_f32(
-            BUBBLE_INTERVAL_SECONDS * 0.5,
-        ))),
-    );
+    let config: BubblesConfig = config_section(app, "bubbles");
+
+    app.add_systems(Startup, setup_bubble_stones_system)
+        .add_systems(Update, place_bubble_stone_system)
+        .add_systems(
+            FixedUpdate,
+            (release_bubbles_system, rise_bubbles_system, splash_system).chain(),
+        )
+        .register_required_components::<Bubble, Interpolated>()
+        .register_required_components::<SplashDroplet, Interpolated>()
+        .insert_resource(config)
+        .init_resource::<BubblerRate>()
+        .add_event::<BubbleStoneEvent>();
+}
+
+/// The `[bubbles]` table of the config file.
+#[derive(Resource, Deserialize)]
+#[serde(default)]
+struct BubblesConfig {
+    /// Stones in the tank from the start.
+    stones: Vec<StoneConfig>,
+    /// Rate of stones placed while running.
+    rate: f32,
+    pop_sound: bool,
 }
 
-#[derive(Resource, Deref)]
-pub struct BubblesEffect(Handle<EffectAsset>);
+impl Default for BubblesConfig {
+    fn default() -> Self {
+        Self {
+            stones: vec![StoneConfig { x: -0.9, rate: 1. }],
+            rate: 1.,
+            pop_sound: true,
+        }
+    }
+}
 
+#[derive(Deserialize, Clone, Copy)]
+struct StoneConfig {
+    x: f32,
+    /// Multiplier on how often the stone lets out a bubble.
+    rate: f32,
+}
+
+/// Multiplier on how often every bubble stone lets out a bubble.
+#[derive(Resource, Deref, DerefMut)]
+pub struct BubblerRate(pub f32);
+
+impl Default for BubblerRate {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+/// A stone on the gravel with a stream of bubbles rising from it.
 #[derive(Component)]
-pub struct GravelBubbler;
+pub struct BubbleStone {
+    rate: f32,
+    /// Bubbles owed since the last one was let out.
+    due: f32,
+    released: u32,
+}
 
-fn bubbles_setup_system(mut commands: Commands, mut effects: ResMut<Assets<EffectAsset>>) {
-    let writer = ExprWriter::new();
+/// Places a bubble stone at the given x, or takes away the one already there.
+#[derive(Event, Deref)]
+pub struct BubbleStoneEvent(pub f32);
 
-    let init_size = SetAttributeModifier {
-        attribute: Attribute::SIZE,
-        value: writer.lit(0.01).expr(),
-    };
+#[derive(Component)]
+struct Bubble {
+    origin: Vec3,
+    /// How far the current has carried the bubble from above its stone.
+    drift: Vec3,
+    speed: f32,
+    age: f32,
+    phase: f32,
+}
 
-    let init_age = SetAttributeModifier::new(Attribute::AGE, writer.lit(0.).expr());
+#[derive(Component)]
+struct SplashDroplet {
+    velocity: Vec3,
+    age: f32,
+}
 
-    let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, writer.lit(3.).expr());
+#[derive(Resource)]
+struct BubbleStoneAssets {
+    stone_mesh: Handle<Mesh>,
+    stone_material: Handle<StandardMaterial>,
+    bubble_mesh: Handle<Mesh>,
+    bubble_material: Handle<StandardMaterial>,
+    droplet_mesh: Handle<Mesh>,
+}
 
-    let init_pos = SetAttributeModifier {
-        attribute: Attribute::POSITION,
-        value: WriterExpr::vec3(writer.lit(0.0), writer.lit(0.0), writer.lit(0.0)).expr(),
-    };
+impl BubbleStoneAssets {
+    fn stone(&self, x: f32, rate: f32) -> impl Bundle {
+        (
+            BubbleStone {
+                rate,
+                due: 0.,
+                released: 0,
+            },
+            Mesh3d(self.stone_mesh.clone()),
+            MeshMaterial3d(self.stone_material.clone()),
+            Transform::from_xyz(x.clamp(-1.7, 1.7), BUBBLE_STONE_HEIGHT, 0.1),
+        )
+    }
+}
 
-    let init_vel = SetAttributeModifier {
-        attribute: Attribute::VELOCITY,
-        value: WriterExpr::vec3(writer.lit(0.0), writer.lit(0.2), writer.lit(0.0)).expr(),
+fn setup_bubble_stones_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    config: Res<BubblesConfig>,
+) {
+    let bubble_color = Color::srgba(0.9, 0.95, 1., 0.5);
+    let assets = BubbleStoneAssets {
+        stone_mesh: meshes.add(Cylinder::new(0.07, 0.04)),
+        stone_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.35, 0.35, 0.4),
+            perceptual_roughness: 1.,
+            ..default()
+        }),
+        bubble_mesh: meshes.add(Sphere::new(0.02)),
+        bubble_material: materials.add(StandardMaterial {
+            base_color: bubble_color,
+            emissive: bubble_color.to_linear() * 0.5,
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }),
+        droplet_mesh: meshes.add(Sphere::new(0.008)),
     };
 
-    let mut color_gradient = Gradient::new();
-    color_gradient.add_key(0.0, Vec4::new(0.9, 0.9, 1.0, 0.8));
-    color_gradient.add_key(0.7, Vec4::new(0.8, 0.8, 1.0, 0.7));
-    color_gradient.add_key(1.0, Vec4::new(0.8, 0.8, 1.0, 0.0));
+    for stone in &config.stones {
+        commands.spawn(assets.stone(stone.x, stone.rate));
+    }
 
-    let update_color = ColorOverLifetimeModifier {
-        gradient: color_gradient,
-        ..default()
-    };
+    commands.insert_resource(assets);
+}
 
-    let mut size_gradient = Gradient::new();
-    size_gradient.add_key(0.0, Vec3::splat(0.05));
-    size_gradient.add_key(0.3, Vec3::splat(0.05));
-    size_gradient.add_key(1.0, Vec3::splat(0.02));
+fn place_bubble_stone_system(
+    mut commands: Commands,
+    mut stone_events: EventReader<BubbleStoneEvent>,
+    stones: Query<(Entity, &Transform), With<BubbleStone>>,
+    assets: Res<BubbleStoneAssets>,
+    config: Res<BubblesConfig>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for x in stone_events.read() {
+        if let Some((entity, _)) = stones.iter().find(|(_, transform)| {
+            (transform.translation.x - **x).abs() < BUBBLE_STONE_REMOVE_DISTANCE
+        }) {
+            commands.entity(entity).despawn();
+            continue;
+        }
 
-    let update_size = SizeOverLifetimeModifier {
-        gradient: size_gradient,
-        screen_space_size: false,
-    };
+        if stones.iter().len() >= BUBBLE_STONES_MAX {
+            toast_events.write(ToastEvent("no room for another bubble stone".into()));
+            continue;
+        }
 
-    let bubbles_effect = effects.add(
-        EffectAsset::new(
-            32768,
-            SpawnerSettings::rate((1. / BUBBLE_INTERVAL_SECONDS).into()),
-            writer.clone().finish(),
-        )
-        .init(init_size)
-        .init(init_pos)
-        .init(init_vel)
-        .init(init_age)
-        .init(init_lifetime)
-        .render(update_size.clone())
-        .render(update_color.clone()),
-    );
-
-    commands.spawn((
-        GravelBubbler,
-        ParticleEffect::new(bubbles_effect.clone()),
-        Transform::from_xyz(0., -1.7, 0.15),
-    ));
-
-    commands.insert_resource(BubblesEffect(bubbles_effect));
+        commands.spawn(assets.stone(**x, config.rate));
+    }
+}
+
+fn release_bubbles_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    rate: Res<BubblerRate>,
+    assets: Res<BubbleStoneAssets>,
+    mut stones: Query<(&mut BubbleStone, &Transform)>,
+) {
+    for (mut stone, transform) in stones.iter_mut() {
+        stone.due += time.delta_secs() * STONE_BUBBLES_PER_SECOND * stone.rate.max(0.) * **rate;
+
+        while stone.due >= 1. {
+            stone.due -= 1.;
+            stone.released += 1;
+
+            // spread the bubbles around the wobble by the golden angle, so that no two match.
+            commands.spawn((
+                Bubble {
+                    origin: transform.translation,
+                    drift: Vec3::ZERO,
+                    speed: BUBBLE_START_SPEED,
+                    age: 0.,
+                    phase: stone.released as f32 * 2.4,
+                },
+                Mesh3d(assets.bubble_mesh.clone()),
+                MeshMaterial3d(assets.bubble_material.clone()),
+                Transform::from_translation(transform.translation),
+            ));
+        }
+    }
+}
+
+/// Bubbles speed up as they rise and sway more the higher they get, then pop at the surface.
+fn rise_bubbles_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    assets: Res<BubbleStoneAssets>,
+    config: Res<BubblesConfig>,
+    current: Res<Current>,
+    mut bubbles: Query<(Entity, &mut Bubble, &mut Transform)>,
+    mut sfx_events: EventWriter<SfxEvent>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, mut bubble, mut transform) in bubbles.iter_mut() {
+        bubble.age += delta;
+        bubble.speed = (bubble.speed + BUBBLE_BUOYANCY * delta).min(BUBBLE_MAX_SPEED);
+        transform.translation.y += bubble.speed * delta;
+
+        let risen = ((transform.translation.y - bubble.origin.y)
+            / (WATER_SURFACE_HEIGHT - bubble.origin.y))
+            .clamp(0., 1.);
+        // only sideways, since the bubbles rise under their own steam.
+        bubble.drift += current.at(transform.translation).with_y(0.) * delta;
+        let wobble = bubble.age * BUBBLE_WOBBLE_FREQUENCY + bubble.phase;
+        transform.translation.x =
+            (bubble.origin.x + bubble.drift.x + wobble.sin() * BUBBLE_WOBBLE_AMPLITUDE * risen)
+                .clamp(-1.8, 1.8);
+        transform.translation.z = bubble.origin.z
+            + bubble.drift.z
+            + (wobble * 0.7).cos() * BUBBLE_WOBBLE_AMPLITUDE * risen;
+
+        if transform.translation.y < WATER_SURFACE_HEIGHT {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+
+        for droplet in 0..SPLASH_DROPLETS {
+            let angle =
+                droplet as f32 / SPLASH_DROPLETS as f32 * std::f32::consts::TAU + bubble.phase;
+            commands.spawn((
+                SplashDroplet {
+                    velocity: Vec3::new(angle.cos() * 0.3, 0.6, angle.sin() * 0.3),
+                    age: 0.,
+                },
+                Mesh3d(assets.droplet_mesh.clone()),
+                MeshMaterial3d(assets.bubble_material.clone()),
+                *transform,
+            ));
+        }
+
+        if config.pop_sound {
+            sfx_events.write(SfxEvent(Sfx::Bubble, Some(transform.translation.x)));
+        }
+    }
 }
 
-fn gravel_bubbles_mover(
+fn splash_system(
+    mut commands: Commands,
     time: Res<Time>,
-    mut gravel_bubbler: Single<&mut Transform, With<GravelBubbler>>,
+    mut droplets: Query<(Entity, &mut SplashDroplet, &mut Transform)>,
 ) {
-    gravel_bubbler.translation.x = 1.7 * time.elapsed_secs().sin();
+    let delta = time.delta_secs();
+
+    for (entity, mut droplet, mut transform) in droplets.iter_mut() {
+        droplet.age += delta;
+        droplet.velocity.y -= SPLASH_GRAVITY * delta;
+        transform.translation += droplet.velocity * delta;
+        transform.scale = Vec3::splat((1. - droplet.age / SPLASH_SECONDS).max(0.));
+
+        if droplet.age >= SPLASH_SECONDS {
+            commands.entity(entity).despawn();
+        }
+    }
 }
@@ -2,7 +2,7 @@ use bevy::{core_pipeline::bloom::Bloom, prelude::*};
 use bevy_atmosphere::plugin::AtmosphereCamera;
 use bevy_ratatui_camera::RatatuiCamera;
 
-use crate::{general::play_sfx, Flags};
+use crate::audio::{Sfx, SfxEvent};
 
 const LIGHT_INTENSITY_DAYTIME: f32 = 500_000.;
 const LIGHT_INTENSITY_NIGHTTIME: f32 = 500_000.;
@@ -14,22 +14,37 @@ const LIGHT_TRANSLATION_DAYTIME: Vec3 = Vec3::new(1.7, 2.5, 5.);
 const LIGHT_TRANSLATION_NIGHTTIME: Vec3 = Vec3::new(0., -2.5, 5.);
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Startup, (setup_camera_system, setup_sfx_system))
-        .add_systems(Update, toggle_daylight_system)
+    app.add_systems(Startup, setup_camera_system)
+        .add_systems(Update, (toggle_daylight_system, light_level_system).chain())
+        .init_resource::<Daytime>()
+        .init_resource::<LightLevel>()
         .add_event::<DaylightEvent>();
 }
 
 #[derive(Component)]
 pub struct Daylight;
 
-#[derive(Event, Default)]
-pub struct DaylightEvent;
+#[derive(Resource, Deref, DerefMut)]
+pub struct Daytime(bool);
+
+impl Default for Daytime {
+    fn default() -> Self {
+        Self(true)
+    }
+}
 
-#[derive(Resource, Deref)]
-pub struct ClickOnSound(Handle<AudioSource>);
+/// Multiplier on the brightness of the daylight, for dimming the tank without switching to night.
+#[derive(Resource, Deref, DerefMut)]
+pub struct LightLevel(pub f32);
 
-#[derive(Resource, Deref)]
-pub struct ClickOffSound(Handle<AudioSource>);
+impl Default for LightLevel {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+#[derive(Event, Default)]
+pub struct DaylightEvent;
 
 fn setup_camera_system(mut commands: Commands) {
     commands.spawn((
@@ -53,42 +68,50 @@ fn setup_camera_system(mut commands: Commands) {
     ));
 }
 
-fn setup_sfx_system(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.insert_resource(ClickOnSound(
-        asset_server.load("embedded://lifecycler/../assets/on.ogg"),
-    ));
-    commands.insert_resource(ClickOffSound(
-        asset_server.load("embedded://lifecycler/../assets/off.ogg"),
-    ));
-}
-
 fn toggle_daylight_system(
     mut commands: Commands,
     camera: Single<(Entity, Option<&AtmosphereCamera>), With<Camera>>,
     mut light: Single<(&mut PointLight, &mut Transform), With<Daylight>>,
     mut daylight_events: EventReader<DaylightEvent>,
-    flags: Res<Flags>,
-    on_click: Res<ClickOnSound>,
-    off_click: Res<ClickOffSound>,
+    mut daytime: ResMut<Daytime>,
+    mut sfx_events: EventWriter<SfxEvent>,
 ) {
     for _ in daylight_events.read() {
         let (ref camera_entity, ref atmosphere) = *camera;
         let (ref mut light, ref mut light_transform) = *light;
 
         if atmosphere.is_some() {
-            play_sfx(&mut commands, &off_click, &flags);
+            sfx_events.write(SfxEvent(Sfx::ClickOff, None));
             commands.entity(*camera_entity).remove::<AtmosphereCamera>();
-            light.intensity = LIGHT_INTENSITY_NIGHTTIME;
             light.color = LIGHT_COLOR_NIGHTTIME;
             light_transform.translation = LIGHT_TRANSLATION_NIGHTTIME;
+            **daytime = false;
         } else {
-            play_sfx(&mut commands, &on_click, &flags);
+            sfx_events.write(SfxEvent(Sfx::ClickOn, None));
             commands
                 .entity(*camera_entity)
                 .insert(AtmosphereCamera::default());
-            light.intensity = LIGHT_INTENSITY_DAYTIME;
             light.color = LIGHT_COLOR_DAYTIME;
             light_transform.translation = LIGHT_TRANSLATION_DAYTIME;
+            **daytime = true;
         }
     }
 }
+
+fn light_level_system(
+    mut light: Single<&mut PointLight, With<Daylight>>,
+    daytime: Res<Daytime>,
+    level: Res<LightLevel>,
+) {
+    if !daytime.is_changed() && !level.is_changed() {
+        return;
+    }
+
+    let intensity = if **daytime {
+        LIGHT_INTENSITY_DAYTIME
+    } else {
+        LIGHT_INTENSITY_NIGHTTIME
+    };
+
+    light.intensity = intensity * **level;
+}
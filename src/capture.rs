@@ -0,0 +1,420 @@
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, DynamicImage, Frame, RgbaImage,
+};
+use ratatui::{backend::TestBackend, buffer::Buffer, style::Color, Terminal};
+use serde_json::json;
+
+use crate::{cli::Options, toast::ToastEvent};
+
+const RECORD_FRAMES_PER_SECOND: f32 = 20.;
+/// Longest a recording can run, since every frame is held in memory until it is saved.
+pub const RECORD_SECONDS_MAX: f32 = 60.;
+const RECORD_FRAMES_MAX: usize = (RECORD_SECONDS_MAX * RECORD_FRAMES_PER_SECOND) as usize;
+const RECORD_WARMUP_SECONDS: f32 = 1.;
+const GIF_ENCODER_SPEED: i32 = 10;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, start_headless_recording_system)
+        .add_systems(
+            Update,
+            (
+                capture_system,
+                write_captures_system,
+                report_saved_recordings_system,
+            )
+                .chain(),
+        )
+        .init_resource::<Capture>()
+        .init_resource::<SavedRecordings>()
+        .add_event::<CaptureEvent>();
+}
+
+#[derive(Event)]
+pub enum CaptureEvent {
+    Snapshot,
+    ToggleRecording,
+}
+
+/// Stands in for the real terminal when recording without one.
+#[derive(Resource, Deref, DerefMut)]
+pub struct HeadlessTerminal(pub Terminal<TestBackend>);
+
+struct CapturedFrame {
+    at: Duration,
+    buffer: Buffer,
+    image: RgbaImage,
+}
+
+struct Recording {
+    started: Duration,
+    length: Option<Duration>,
+    frames: Vec<CapturedFrame>,
+    outputs: Vec<PathBuf>,
+    /// Whether the recording ran up against its length rather than being stopped.
+    ran_out: bool,
+}
+
+/// Frames on their way to disk, grabbed from the draw system as they are rendered.
+#[derive(Resource, Default)]
+pub struct Capture {
+    snapshot_requested: bool,
+    snapshot: Option<(CapturedFrame, Vec<PathBuf>)>,
+    recording: Option<Recording>,
+    finished: Option<Recording>,
+    headless: bool,
+}
+
+impl Capture {
+    /// Whether the draw system should bother handing over the frame it just drew.
+    pub fn wants_frame(&self) -> bool {
+        self.snapshot_requested || self.recording.is_some()
+    }
+
+    /// Recordings started from the keyboard get a badge, which headless ones leave out.
+    pub fn shows_badge(&self) -> bool {
+        self.recording.is_some() && !self.headless
+    }
+
+    pub fn frame(&mut self, now: Duration, buffer: &Buffer, image: &DynamicImage) {
+        let capture = || CapturedFrame {
+            at: now,
+            buffer: buffer.clone(),
+            image: image.to_rgba8(),
+        };
+
+        if self.snapshot_requested {
+            self.snapshot_requested = false;
+            let stem = capture_stem();
+            self.snapshot = Some((
+                capture(),
+                vec![stem.with_extension("png"), stem.with_extension("ans")],
+            ));
+        }
+
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+
+        if now < recording.started {
+            return;
+        }
+
+        let due = recording
+            .frames
+            .last()
+            .is_none_or(|last| (now - last.at).as_secs_f32() >= 1. / RECORD_FRAMES_PER_SECOND);
+        if due {
+            recording.frames.push(capture());
+        }
+
+        if recording.frames.len() >= RECORD_FRAMES_MAX
+            || recording
+                .length
+                .is_some_and(|length| now - recording.started >= length)
+        {
+            recording.ran_out = true;
+            self.finished = self.recording.take();
+        }
+    }
+}
+
+/// Where captures go when nobody said otherwise, named after the time they were taken.
+fn capture_stem() -> PathBuf {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+
+    PathBuf::from(format!("lifecycler-{seconds}"))
+}
+
+fn start_headless_recording_system(
+    time: Res<Time<Real>>,
+    options: Res<Options>,
+    mut capture: ResMut<Capture>,
+) {
+    let Some(record) = &options.record else {
+        return;
+    };
+
+    capture.headless = true;
+    capture.recording = Some(Recording {
+        started: time.elapsed() + Duration::from_secs_f32(RECORD_WARMUP_SECONDS),
+        length: Some(Duration::from_secs_f32(record.seconds)),
+        frames: Vec::new(),
+        outputs: record.outputs.clone(),
+        ran_out: false,
+    });
+}
+
+fn capture_system(
+    time: Res<Time<Real>>,
+    mut capture_events: EventReader<CaptureEvent>,
+    mut capture: ResMut<Capture>,
+) {
+    for event in capture_events.read() {
+        match event {
+            CaptureEvent::Snapshot => capture.snapshot_requested = true,
+            CaptureEvent::ToggleRecording => {
+                if let Some(recording) = capture.recording.take() {
+                    capture.finished = Some(recording);
+                } else {
+                    let stem = capture_stem();
+                    capture.recording = Some(Recording {
+                        started: time.elapsed(),
+                        length: Some(Duration::from_secs_f32(RECORD_SECONDS_MAX)),
+                        frames: Vec::new(),
+                        outputs: vec![stem.with_extension("gif"), stem.with_extension("cast")],
+                        ran_out: false,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Results of recordings saved in the background, reported as toasts once they are done.
+#[derive(Resource)]
+struct SavedRecordings {
+    sender: Sender<String>,
+    receiver: Mutex<Receiver<String>>,
+}
+
+impl Default for SavedRecordings {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        Self {
+            sender,
+            receiver: Mutex::new(receiver),
+        }
+    }
+}
+
+fn write_captures_system(
+    mut capture: ResMut<Capture>,
+    saved: Res<SavedRecordings>,
+    mut toast_events: EventWriter<ToastEvent>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if let Some((frame, outputs)) = capture.snapshot.take() {
+        for output in outputs {
+            let written = match extension(&output) {
+                "png" => frame.image.save(&output).map_err(|error| error.to_string()),
+                _ => std::fs::write(&output, buffer_to_ansi(&frame.buffer))
+                    .map_err(|error| error.to_string()),
+            };
+            toast_events.write(ToastEvent(describe(&output, written)));
+        }
+    }
+
+    let Some(recording) = capture.finished.take() else {
+        return;
+    };
+
+    // encoding a gif takes a while, which would otherwise stall the tank.
+    if capture.headless {
+        let mut failed = false;
+        for output in &recording.outputs {
+            match write_recording(output, &recording.frames) {
+                Ok(()) => println!("saved {}", output.display()),
+                Err(error) => {
+                    eprintln!("{}: {error}", output.display());
+                    failed = true;
+                }
+            }
+        }
+
+        // scripts making previews go by the exit status to know if they got one.
+        exit.write(if failed {
+            AppExit::error()
+        } else {
+            AppExit::Success
+        });
+    } else {
+        if recording.ran_out {
+            toast_events.write(ToastEvent(format!(
+                "recording stopped at the {RECORD_SECONDS_MAX} second limit"
+            )));
+        }
+
+        toast_events.write(ToastEvent(format!(
+            "saving {}",
+            recording
+                .outputs
+                .iter()
+                .map(|output| output.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" and ")
+        )));
+
+        let sender = saved.sender.clone();
+        std::thread::spawn(move || {
+            for output in &recording.outputs {
+                let written = write_recording(output, &recording.frames);
+                let _ = sender.send(describe(output, written));
+            }
+        });
+    }
+}
+
+fn report_saved_recordings_system(
+    saved: Res<SavedRecordings>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    let Ok(results) = saved.receiver.lock() else {
+        return;
+    };
+
+    for result in results.try_iter() {
+        toast_events.write(ToastEvent(result));
+    }
+}
+
+fn extension(path: &Path) -> &str {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+}
+
+fn describe(output: &Path, written: Result<(), String>) -> String {
+    match written {
+        Ok(()) => format!("saved {}", output.display()),
+        Err(error) => format!("{}: {error}", output.display()),
+    }
+}
+
+fn write_recording(output: &Path, frames: &[CapturedFrame]) -> Result<(), String> {
+    let create = || {
+        File::create(output)
+            .map(BufWriter::new)
+            .map_err(|error| error.to_string())
+    };
+
+    match extension(output) {
+        "gif" => write_gif(create()?, frames),
+        "cast" => write_cast(create()?, frames),
+        other => Err(format!("cannot record to .{other}, only .gif or .cast")),
+    }
+}
+
+fn write_gif(writer: impl Write, frames: &[CapturedFrame]) -> Result<(), String> {
+    if frames.is_empty() {
+        return Err("nothing was recorded".into());
+    }
+
+    let mut encoder = GifEncoder::new_with_speed(writer, GIF_ENCODER_SPEED);
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .map_err(|error| error.to_string())?;
+
+    let delays = frames
+        .windows(2)
+        .map(|pair| pair[1].at - pair[0].at)
+        .chain(std::iter::once(Duration::from_secs_f32(
+            1. / RECORD_FRAMES_PER_SECOND,
+        )));
+
+    encoder
+        .encode_frames(frames.iter().zip(delays).map(|(frame, delay)| {
+            Frame::from_parts(
+                frame.image.clone(),
+                0,
+                0,
+                Delay::from_numer_denom_ms(delay.as_millis() as u32, 1),
+            )
+        }))
+        .map_err(|error| error.to_string())
+}
+
+/// Writes an asciicast v2 file, which is a json header followed by one json event per line.
+fn write_cast(mut writer: impl Write, frames: &[CapturedFrame]) -> Result<(), String> {
+    let Some(first) = frames.first() else {
+        return Err("nothing was recorded".into());
+    };
+
+    let header = json!({
+        "version": 2,
+        "width": first.buffer.area.width,
+        "height": first.buffer.area.height,
+        "timestamp": SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs()),
+    });
+
+    let mut lines = vec![header.to_string()];
+    for frame in frames {
+        let at = (frame.at - first.at).as_secs_f64();
+        let output = format!("\x1b[H{}", buffer_to_ansi(&frame.buffer));
+        lines.push(json!([at, "o", output]).to_string());
+    }
+
+    writeln!(writer, "{}", lines.join("\n")).map_err(|error| error.to_string())
+}
+
+/// The buffer as text, with escape codes for its colors.
+fn buffer_to_ansi(buffer: &Buffer) -> String {
+    let width = buffer.area.width as usize;
+    let mut ansi = String::new();
+
+    for (row, cells) in buffer.content.chunks(width.max(1)).enumerate() {
+        if row > 0 {
+            ansi.push_str("\r\n");
+        }
+
+        let mut colors = None;
+        for cell in cells {
+            if colors != Some((cell.fg, cell.bg)) {
+                colors = Some((cell.fg, cell.bg));
+                let _ = write!(ansi, "\x1b[{};{}m", sgr(cell.fg, 30), sgr(cell.bg, 40));
+            }
+            ansi.push_str(cell.symbol());
+        }
+
+        ansi.push_str("\x1b[0m");
+    }
+
+    ansi
+}
+
+/// The select graphic rendition parameters for a color, from a base of 30 for the foreground
+/// or 40 for the background.
+fn sgr(color: Color, base: u8) -> String {
+    let named = |offset: u8| (base + offset).to_string();
+    let bright = |offset: u8| (base + 60 + offset).to_string();
+
+    match color {
+        Color::Reset => named(9),
+        Color::Black => named(0),
+        Color::Red => named(1),
+        Color::Green => named(2),
+        Color::Yellow => named(3),
+        Color::Blue => named(4),
+        Color::Magenta => named(5),
+        Color::Cyan => named(6),
+        Color::Gray => named(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Indexed(index) => format!("{};5;{index}", base + 8),
+        Color::Rgb(r, g, b) => format!("{};2;{r};{g};{b}", base + 8),
+    }
+}
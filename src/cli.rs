@@ -0,0 +1,218 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use crate::{capture::RECORD_SECONDS_MAX, colors::ColorDepth, render::RenderMode};
+
+pub const USAGE: &str = "\
+usage:
+  lifecycler [--control] [--socket <path>] [--watch <repo>]... [--render <mode>]
+             [--colors <depth>] [--dither]
+  lifecycler ctl [--socket <path>] <command>
+  lifecycler hook install [<repo>]
+  lifecycler record [--seconds <n>] [--out <path>]... [--size <cols>x<rows>]
+
+options:
+  --control          listen for commands on a local socket
+  --socket <path>    socket to listen on or send to
+  --watch <repo>     drop a pellet for every commit made in a repository
+                     with the hook installed
+  --render <mode>    auto, half-blocks, braille, ascii, edges or graphics
+  --colors <depth>   16, 256 or truecolor, instead of asking the terminal
+  --dither           dither when there are fewer than 24-bit colors
+  -h, --help         print this message
+
+recording, without a terminal:
+  --seconds <n>      how long to record for, 10 by default and 60 at most
+  --out <path>       a .gif or .cast file to write, lifecycler.cast by default
+  --size <cols>x<rows>
+                     the size of the pretend terminal, 100x50 by default
+
+commands:
+  feed [x]           drop a pellet, at x between -1.7 and 1.7 or in the middle
+  daylight on|off    switch between day and night
+  mute [on|off]      mute, unmute or toggle sound
+  spawn fish         add a fish to the tank
+  stats              print the current statistics
+  {...}              send a raw json request";
+
+/// Launch options for the aquarium itself.
+#[derive(Resource, Default, Clone)]
+pub struct Options {
+    /// Where the control server listens, if it is enabled.
+    pub control_socket: Option<PathBuf>,
+    /// Repositories whose commits feed the fish.
+    pub watch_repos: Vec<PathBuf>,
+    pub render_mode: RenderMode,
+    /// Overrides the color depth detected from the environment.
+    pub color_depth: Option<ColorDepth>,
+    pub dither: bool,
+    /// Set when recording headlessly instead of running in a terminal.
+    pub record: Option<RecordOptions>,
+}
+
+#[derive(Clone)]
+pub struct RecordOptions {
+    pub seconds: f32,
+    pub outputs: Vec<PathBuf>,
+    pub size: (u16, u16),
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        Self {
+            seconds: 10.,
+            outputs: Vec::new(),
+            size: (100, 50),
+        }
+    }
+}
+
+pub enum Command {
+    Run(Options),
+    Ctl {
+        socket: Option<PathBuf>,
+        words: Vec<String>,
+    },
+    HookInstall {
+        repo: PathBuf,
+    },
+    Help,
+}
+
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter().peekable();
+
+    if args.peek().is_some_and(|arg| arg == "ctl") {
+        args.next();
+
+        let mut socket = None;
+        let mut words = Vec::new();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--socket" => socket = Some(socket_path(args.next())?),
+                "-h" | "--help" => return Ok(Command::Help),
+                _ => words.push(arg),
+            }
+        }
+
+        if words.is_empty() {
+            return Err("missing command for ctl".into());
+        }
+
+        return Ok(Command::Ctl { socket, words });
+    }
+
+    if args.peek().is_some_and(|arg| arg == "hook") {
+        args.next();
+
+        if args.next().as_deref() != Some("install") {
+            return Err("expected \"hook install\"".into());
+        }
+
+        let repo = args
+            .next()
+            .map_or_else(|| PathBuf::from("."), PathBuf::from);
+        if let Some(arg) = args.next() {
+            return Err(format!("unexpected argument \"{arg}\""));
+        }
+
+        return Ok(Command::HookInstall { repo });
+    }
+
+    let mut record = args.next_if_eq("record").map(|_| RecordOptions::default());
+
+    let mut control = false;
+    let mut socket = None;
+    let mut watch_repos = Vec::new();
+    let mut render_mode = RenderMode::default();
+    let mut color_depth = None;
+    let mut dither = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--control" => control = true,
+            "--socket" => socket = Some(socket_path(args.next())?),
+            "--watch" => watch_repos.push(
+                args.next()
+                    .map(PathBuf::from)
+                    .ok_or("missing repository after --watch")?,
+            ),
+            "--render" => {
+                render_mode = args.next().ok_or("missing mode after --render")?.parse()?
+            }
+            "--colors" => {
+                color_depth = Some(args.next().ok_or("missing depth after --colors")?.parse()?)
+            }
+            "--dither" => dither = true,
+            "--seconds" | "--out" | "--size" if record.is_none() => {
+                return Err(format!("{arg} only makes sense with record"))
+            }
+            "--seconds" => {
+                let seconds = args.next().ok_or("missing number after --seconds")?;
+                let seconds: f32 = seconds
+                    .parse()
+                    .map_err(|_| format!("expected a number of seconds, not \"{seconds}\""))?;
+                if !(seconds > 0. && seconds <= RECORD_SECONDS_MAX) {
+                    return Err(format!(
+                        "--seconds should be more than 0 and at most {RECORD_SECONDS_MAX}"
+                    ));
+                }
+                record.as_mut().unwrap().seconds = seconds;
+            }
+            "--out" => {
+                let output = PathBuf::from(args.next().ok_or("missing path after --out")?);
+                if !matches!(
+                    output.extension().and_then(|extension| extension.to_str()),
+                    Some("gif" | "cast")
+                ) {
+                    return Err(format!("{} should end in .gif or .cast", output.display()));
+                }
+                record.as_mut().unwrap().outputs.push(output);
+            }
+            "--size" => {
+                let size = args.next().ok_or("missing size after --size")?;
+                record.as_mut().unwrap().size = size
+                    .split_once('x')
+                    .and_then(|(columns, rows)| Some((columns.parse().ok()?, rows.parse().ok()?)))
+                    .ok_or_else(|| format!("expected a size like 100x50, not \"{size}\""))?;
+            }
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("unexpected argument \"{arg}\"")),
+        }
+    }
+
+    if let Some(record) = record.as_mut().filter(|record| record.outputs.is_empty()) {
+        record.outputs.push(PathBuf::from("lifecycler.cast"));
+    }
+
+    let control_socket = match (control, socket) {
+        (_, Some(socket)) => Some(socket),
+        (true, None) => Some(default_socket_path()),
+        (false, None) => None,
+    };
+
+    Ok(Command::Run(Options {
+        control_socket,
+        watch_repos,
+        render_mode,
+        color_depth,
+        dither,
+        record,
+    }))
+}
+
+fn socket_path(arg: Option<String>) -> Result<PathBuf, String> {
+    arg.map(PathBuf::from)
+        .ok_or_else(|| "missing path after --socket".into())
+}
+
+/// The per-user runtime directory if there is one, so that each user gets their own socket.
+pub fn default_socket_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join("lifecycler.sock"),
+        None => std::env::temp_dir().join(format!(
+            "lifecycler-{}.sock",
+            std::env::var("USER").unwrap_or_default()
+        )),
+    }
+}
@@ -0,0 +1,171 @@
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use ratatui::{buffer::Buffer, style::Color};
+
+use crate::cli::Options;
+
+/// Levels of each channel in the 6x6x6 color cube of the xterm-256 palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The basic ANSI colors, as xterm draws them by default.
+const ANSI_COLORS: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::Red, [205, 0, 0]),
+    (Color::Green, [0, 205, 0]),
+    (Color::Yellow, [205, 205, 0]),
+    (Color::Blue, [0, 0, 238]),
+    (Color::Magenta, [205, 0, 205]),
+    (Color::Cyan, [0, 205, 205]),
+    (Color::Gray, [229, 229, 229]),
+    (Color::DarkGray, [127, 127, 127]),
+    (Color::LightRed, [255, 0, 0]),
+    (Color::LightGreen, [0, 255, 0]),
+    (Color::LightYellow, [255, 255, 0]),
+    (Color::LightBlue, [92, 92, 255]),
+    (Color::LightMagenta, [255, 0, 255]),
+    (Color::LightCyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, choose_color_output_system);
+}
+
+/// How many colors the terminal can show.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorDepth {
+    Ansi16,
+    Xterm256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Goes by `COLORTERM` and `TERM`, unless told otherwise on the command line.
+    pub fn resolve(requested: Option<Self>) -> Self {
+        if let Some(depth) = requested {
+            return depth;
+        }
+
+        let term = std::env::var("TERM").unwrap_or_default();
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+
+        if colorterm.contains("truecolor") || colorterm.contains("24bit") {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Xterm256
+        } else {
+            Self::Ansi16
+        }
+    }
+}
+
+impl FromStr for ColorDepth {
+    type Err = String;
+
+    fn from_str(depth: &str) -> Result<Self, Self::Err> {
+        match depth {
+            "16" => Ok(Self::Ansi16),
+            "256" => Ok(Self::Xterm256),
+            "truecolor" | "24bit" => Ok(Self::TrueColor),
+            _ => Err(format!("unknown color depth \"{depth}\"")),
+        }
+    }
+}
+
+#[derive(Resource, Clone, Copy)]
+pub struct ColorOutput {
+    pub depth: ColorDepth,
+    pub dither: bool,
+}
+
+impl ColorOutput {
+    /// Swaps every 24-bit color in the buffer for the nearest one the terminal can show.
+    pub fn quantize(&self, buffer: &mut Buffer) {
+        if self.depth == ColorDepth::TrueColor {
+            return;
+        }
+
+        let width = buffer.area.width.max(1) as usize;
+        for (index, cell) in buffer.content.iter_mut().enumerate() {
+            let (x, y) = (index % width, index / width);
+
+            // half blocks stack two pixels in a cell, so each gets its own row of the pattern.
+            let fg = self.quantize_color(cell.fg, x, y * 2);
+            let bg = self.quantize_color(cell.bg, x, y * 2 + 1);
+            cell.fg = fg;
+            cell.bg = bg;
+        }
+    }
+
+    fn quantize_color(&self, color: Color, x: usize, y: usize) -> Color {
+        let Color::Rgb(r, g, b) = color else {
+            return color;
+        };
+
+        // nudges each channel up or down by up to half a palette step, in an ordered pattern.
+        let spread = match self.depth {
+            ColorDepth::Ansi16 => 96.,
+            _ => 40.,
+        };
+        let offset = if self.dither {
+            (BAYER_4X4[y % 4][x % 4] as f32 / 16. - 0.5) * spread
+        } else {
+            0.
+        };
+        let rgb = [r, g, b].map(|channel| (channel as f32 + offset).clamp(0., 255.) as u8);
+
+        match self.depth {
+            ColorDepth::Ansi16 => nearest_ansi(rgb),
+            ColorDepth::Xterm256 => Color::Indexed(nearest_xterm(rgb)),
+            ColorDepth::TrueColor => color,
+        }
+    }
+}
+
+fn choose_color_output_system(mut commands: Commands, options: Res<Options>) {
+    commands.insert_resource(ColorOutput {
+        depth: ColorDepth::resolve(options.color_depth),
+        dither: options.dither,
+    });
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (*a as i32 - b as i32).pow(2) as u32)
+        .sum()
+}
+
+fn nearest_ansi(rgb: [u8; 3]) -> Color {
+    ANSI_COLORS
+        .iter()
+        .min_by_key(|(_, ansi)| distance(rgb, *ansi))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
+
+/// The closest of the color cube and the grayscale ramp, leaving out the 16 system colors
+/// since terminals are free to redefine them.
+fn nearest_xterm(rgb: [u8; 3]) -> u8 {
+    let level = |channel: u8| {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| (channel as i32 - **level as i32).abs())
+            .map_or(0, |(index, _)| index)
+    };
+    let [r, g, b] = rgb.map(level);
+    let cube = [r, g, b].map(|index| CUBE_LEVELS[index]);
+
+    let average = rgb.iter().map(|channel| *channel as u32).sum::<u32>() / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + gray_index * 10;
+
+    if distance(rgb, [gray; 3]) < distance(rgb, cube) {
+        232 + gray_index
+    } else {
+        16 + (36 * r + 6 * g + b) as u8
+    }
+}
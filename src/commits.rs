@@ -0,0 +1,287 @@
+use std::{
+    fs::File,
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+};
+
+use bevy::prelude::*;
+use notify::{RecursiveMode, Watcher};
+use rand_chacha::rand_core::RngCore;
+
+use crate::{
+    cli::Options,
+    pellets::{PelletEvent, PelletRng, PELLET_SURFACE_HEIGHT},
+    toast::ToastEvent,
+};
+
+/// Written to by the post-commit hook, one tab-separated line per commit, inside the git dir.
+const COMMIT_LOG: &str = "lifecycler-commits";
+const HOOK_MARKER: &str = "# lifecycler: feed the fish on every commit";
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, start_commit_watcher_system)
+        .add_systems(
+            Update,
+            commit_feed_system.run_if(resource_exists::<CommitFeed>),
+        );
+}
+
+pub struct Commit {
+    pub hash: String,
+    pub author: String,
+    pub summary: String,
+}
+
+/// Commits picked up by the watcher thread, or problems it ran into along the way.
+#[derive(Resource, Deref)]
+pub struct CommitFeed(Mutex<Receiver<Result<Commit, String>>>);
+
+/// What `install_hook` did to the post-commit hook.
+pub enum HookInstall {
+    Created(PathBuf),
+    /// An existing hook had the logging line added straight after its `#!` line, so that it
+    /// runs even if the rest of the hook exits early.
+    Extended(PathBuf),
+}
+
+/// Adds a post-commit hook to the repository at `repo`, keeping any hook already there.
+pub fn install_hook(repo: &Path) -> Result<HookInstall, String> {
+    let hooks = repo.join(git(repo, &["rev-parse", "--git-path", "hooks"])?);
+    let hook = hooks.join("post-commit");
+
+    let existing = std::fs::read_to_string(&hook).unwrap_or_default();
+    if existing.contains(HOOK_MARKER) {
+        return Err(format!("{} already feeds the fish", hook.display()));
+    }
+
+    let feed = format!(
+        "{HOOK_MARKER}\ngit log -1 --format='%h%x09%an%x09%s' >> \"$(git rev-parse --git-dir)/{COMMIT_LOG}\"\n"
+    );
+
+    let (script, installed) = if existing.trim().is_empty() {
+        (
+            format!("#!/bin/sh\n\n{feed}"),
+            HookInstall::Created(hook.clone()),
+        )
+    } else {
+        let (shebang, rest) = existing.split_once('\n').unwrap_or((&existing, ""));
+        if !is_sh_script(shebang) {
+            return Err(format!(
+                "{} is not a sh script, add this to it by hand:\n\n{feed}",
+                hook.display()
+            ));
+        }
+
+        (
+            format!("{shebang}\n{feed}{rest}"),
+            HookInstall::Extended(hook.clone()),
+        )
+    };
+
+    std::fs::create_dir_all(&hooks)
+        .and_then(|_| std::fs::write(&hook, script))
+        .map_err(|error| format!("{}: {error}", hook.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755))
+            .map_err(|error| format!("{}: {error}", hook.display()))?;
+    }
+
+    Ok(installed)
+}
+
+/// Whether a hook's `#!` line runs it with a shell the logging line works in.
+fn is_sh_script(shebang: &str) -> bool {
+    let Some(command) = shebang.strip_prefix("#!") else {
+        return false;
+    };
+
+    let mut words = command.split_whitespace();
+    let interpreter = match words.next() {
+        Some(env) if env.ends_with("/env") => words.next(),
+        interpreter => interpreter,
+    };
+
+    interpreter
+        .and_then(|interpreter| Path::new(interpreter).file_name())
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| matches!(name, "sh" | "bash" | "dash" | "zsh"))
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|error| format!("could not run git: {error}"))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "{}: {}",
+            repo.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Reads whatever complete lines have been appended to a commit log since last time.
+struct CommitLog {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl CommitLog {
+    fn new(git_dir: &Path) -> Self {
+        let path = git_dir.join(COMMIT_LOG);
+        let offset = path.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+
+        Self { path, offset }
+    }
+
+    fn read_new(&mut self) -> Vec<Commit> {
+        let Ok(mut file) = File::open(&self.path) else {
+            self.offset = 0;
+            return vec![];
+        };
+
+        if file
+            .metadata()
+            .is_ok_and(|metadata| metadata.len() < self.offset)
+        {
+            self.offset = 0;
+        }
+
+        let mut appended = String::new();
+        if file.seek(SeekFrom::Start(self.offset)).is_err()
+            || file.read_to_string(&mut appended).is_err()
+        {
+            return vec![];
+        }
+
+        // a line without its newline is still being written, and is picked up next time.
+        let complete = appended.rfind('\n').map_or(0, |index| index + 1);
+        self.offset += complete as u64;
+
+        appended[..complete]
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, '\t');
+                Some(Commit {
+                    hash: fields.next()?.to_string(),
+                    author: fields.next()?.to_string(),
+                    summary: fields.next().unwrap_or_default().to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn start_commit_watcher_system(
+    mut commands: Commands,
+    options: Res<Options>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if options.watch_repos.is_empty() {
+        return;
+    }
+
+    let mut git_dirs = vec![];
+    for repo in &options.watch_repos {
+        match git(repo, &["rev-parse", "--absolute-git-dir"]) {
+            Ok(git_dir) => git_dirs.push(PathBuf::from(git_dir)),
+            Err(error) => {
+                toast_events.write(ToastEvent(error));
+            }
+        }
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || watch_commits(git_dirs, sender));
+
+    commands.insert_resource(CommitFeed(Mutex::new(receiver)));
+}
+
+/// Runs for as long as the app does, holding on to the filesystem watcher.
+fn watch_commits(git_dirs: Vec<PathBuf>, sender: Sender<Result<Commit, String>>) {
+    let (notify_sender, notify_events) = mpsc::channel();
+
+    let mut watcher = match notify::recommended_watcher(notify_sender) {
+        Ok(watcher) => watcher,
+        Err(error) => {
+            let _ = sender.send(Err(format!("could not watch for commits: {error}")));
+            return;
+        }
+    };
+
+    // the git dir is watched rather than the log itself, which only appears after the first commit.
+    let mut logs = vec![];
+    for git_dir in git_dirs {
+        match watcher.watch(&git_dir, RecursiveMode::NonRecursive) {
+            Ok(()) => logs.push(CommitLog::new(&git_dir)),
+            Err(error) => {
+                let _ = sender.send(Err(format!("{}: {error}", git_dir.display())));
+            }
+        }
+    }
+
+    for event in notify_events.into_iter().flatten() {
+        for log in logs
+            .iter_mut()
+            .filter(|log| event.paths.contains(&log.path))
+        {
+            for commit in log.read_new() {
+                if sender.send(Ok(commit)).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn commit_feed_system(
+    feed: Res<CommitFeed>,
+    mut pellet_rng: ResMut<PelletRng>,
+    mut pellet_events: EventWriter<PelletEvent>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    let Ok(commits) = feed.lock() else {
+        return;
+    };
+
+    for commit in commits.try_iter() {
+        let commit = match commit {
+            Ok(commit) => commit,
+            Err(error) => {
+                toast_events.write(ToastEvent(error));
+                continue;
+            }
+        };
+
+        // each author always gets the same color of pellet.
+        let mut hasher = DefaultHasher::new();
+        commit.author.hash(&mut hasher);
+
+        let x = pellet_rng.next_u32() as f32 / u32::MAX as f32 * 3.4 - 1.7;
+        pellet_events.write(PelletEvent(
+            Transform::from_xyz(x, PELLET_SURFACE_HEIGHT, 0.),
+            Some(hasher.finish() as usize),
+        ));
+
+        toast_events.write(ToastEvent(format!(
+            "{} {}: {}",
+            commit.hash, commit.author, commit.summary
+        )));
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+
+use crate::{general::config_dir, toast::ToastEvent};
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// Reads the config file, which has to happen before any plugin that reads a section of it.
+pub(super) fn plugin(app: &mut App) {
+    let mut problems = Vec::new();
+
+    let table = config_dir()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .and_then(|path| {
+            let contents = std::fs::read_to_string(&path).ok()?;
+            contents
+                .parse::<toml::Table>()
+                .map_err(|error| problems.push(format!("{}: {}", path.display(), error.message())))
+                .ok()
+        })
+        .unwrap_or_default();
+
+    app.add_systems(Startup, report_config_problems_system)
+        .insert_resource(ConfigFile(table))
+        .insert_resource(ConfigProblems(problems));
+}
+
+#[derive(Resource, Default, Deref)]
+pub struct ConfigFile(toml::Table);
+
+/// Anything in the config file that could not be used, shown as toasts once the tank is up.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ConfigProblems(Vec<String>);
+
+/// Deserializes the `[name]` table of the config file, falling back to the default when the
+/// table is missing or cannot be used.
+pub fn config_section<T: DeserializeOwned + Default>(app: &mut App, name: &str) -> T {
+    let Some(section) = app.world().resource::<ConfigFile>().get(name).cloned() else {
+        return T::default();
+    };
+
+    section.try_into().unwrap_or_else(|error: toml::de::Error| {
+        app.world_mut()
+            .resource_mut::<ConfigProblems>()
+            .push(format!("[{name}]: {}", error.message()));
+        T::default()
+    })
+}
+
+fn report_config_problems_system(
+    problems: Res<ConfigProblems>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for problem in problems.iter() {
+        toast_events.write(ToastEvent(format!("config: {problem}")));
+    }
+}
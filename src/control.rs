@@ -0,0 +1,258 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    os::unix::{
+        fs::FileTypeExt,
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    camera::{DaylightEvent, Daytime},
+    cli::Options,
+    creatures::{behavior::CreatureRng, fish_systems::FishSpawnEvent},
+    pellets::{PelletEvent, PELLET_SURFACE_HEIGHT},
+    stats::Stats,
+    temperature::WaterTemperature,
+    toast::ToastEvent,
+    Flags,
+};
+
+const FEED_X_LIMIT: f32 = 1.7;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, start_control_server_system)
+        .add_systems(
+            Update,
+            control_requests_system.run_if(resource_exists::<ControlServer>),
+        );
+}
+
+/// One request per line, e.g. `{"cmd": "daylight", "on": false}`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum ControlCommand {
+    Feed { x: Option<f32> },
+    Daylight { on: bool },
+    Mute { on: Option<bool> },
+    Spawn { creature: String },
+    Stats,
+}
+
+impl ControlCommand {
+    /// Reads a command the way it is typed after `lifecycler ctl`.
+    pub fn from_words(words: &[String]) -> Result<Self, String> {
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+        let switch = |word: &str| match word {
+            "on" => Ok(true),
+            "off" => Ok(false),
+            _ => Err(format!("expected on or off, not \"{word}\"")),
+        };
+
+        match words.as_slice() {
+            ["feed"] => Ok(Self::Feed { x: None }),
+            ["feed", x] => x
+                .parse()
+                .map(|x| Self::Feed { x: Some(x) })
+                .map_err(|_| format!("expected a number, not \"{x}\"")),
+            ["daylight", on] => Ok(Self::Daylight { on: switch(on)? }),
+            ["mute"] => Ok(Self::Mute { on: None }),
+            ["mute", on] => Ok(Self::Mute {
+                on: Some(switch(on)?),
+            }),
+            ["spawn", creature] => Ok(Self::Spawn {
+                creature: creature.to_string(),
+            }),
+            ["stats"] => Ok(Self::Stats),
+            _ => Err(format!("unknown command \"{}\"", words.join(" "))),
+        }
+    }
+}
+
+/// Requests forwarded from the connection threads, each with somewhere to send the reply.
+#[derive(Resource)]
+pub struct ControlServer {
+    path: PathBuf,
+    requests: Mutex<Receiver<ControlRequest>>,
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+struct ControlRequest {
+    command: Result<ControlCommand, String>,
+    reply: Sender<String>,
+}
+
+fn start_control_server_system(
+    mut commands: Commands,
+    options: Res<Options>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    let Some(path) = options.control_socket.clone() else {
+        return;
+    };
+
+    match std::fs::symlink_metadata(&path) {
+        Ok(metadata) if !metadata.file_type().is_socket() => {
+            toast_events.write(ToastEvent(format!(
+                "control server: {} is not a socket, leaving it alone",
+                path.display()
+            )));
+            return;
+        }
+        // a socket nobody is listening on is left over from a run that did not clean up.
+        Ok(_) if UnixStream::connect(&path).is_err() => {
+            let _ = std::fs::remove_file(&path);
+        }
+        _ => {}
+    }
+
+    let listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(error) => {
+            toast_events.write(ToastEvent(format!(
+                "control server: {}: {error}",
+                path.display()
+            )));
+            return;
+        }
+    };
+
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let sender = sender.clone();
+            std::thread::spawn(move || handle_connection(stream, sender));
+        }
+    });
+
+    commands.insert_resource(ControlServer {
+        path,
+        requests: Mutex::new(receiver),
+    });
+}
+
+fn handle_connection(stream: UnixStream, sender: Sender<ControlRequest>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (reply, response) = mpsc::channel();
+        let command = serde_json::from_str(&line).map_err(|error| error.to_string());
+
+        if sender.send(ControlRequest { command, reply }).is_err() {
+            break;
+        }
+
+        let Ok(response) = response.recv() else {
+            break;
+        };
+
+        if writeln!(writer, "{response}").is_err() {
+            break;
+        }
+    }
+}
+
+fn control_requests_system(
+    server: Res<ControlServer>,
+    mut flags: ResMut<Flags>,
+    daytime: Res<Daytime>,
+    stats: Res<Stats>,
+    temperature: Res<WaterTemperature>,
+    mut rng: ResMut<CreatureRng>,
+    mut pellet_events: EventWriter<PelletEvent>,
+    mut daylight_events: EventWriter<DaylightEvent>,
+    mut spawn_events: EventWriter<FishSpawnEvent>,
+) {
+    let Ok(requests) = server.requests.lock() else {
+        return;
+    };
+
+    for request in requests.try_iter() {
+        let response = match request.command {
+            Ok(ControlCommand::Feed { x }) => {
+                let x = x.unwrap_or(0.).clamp(-FEED_X_LIMIT, FEED_X_LIMIT);
+                pellet_events.write(PelletEvent(
+                    Transform::from_xyz(x, PELLET_SURFACE_HEIGHT, 0.),
+                    None,
+                ));
+                json!({ "ok": true })
+            }
+            Ok(ControlCommand::Daylight { on }) => {
+                if on != **daytime {
+                    daylight_events.write_default();
+                }
+                json!({ "ok": true })
+            }
+            Ok(ControlCommand::Mute { on }) => {
+                flags.muted = on.unwrap_or(!flags.muted);
+                json!({ "ok": true, "muted": flags.muted })
+            }
+            Ok(ControlCommand::Spawn { creature }) if creature == "fish" => {
+                spawn_events.write(FishSpawnEvent::random(&mut rng));
+                json!({ "ok": true })
+            }
+            Ok(ControlCommand::Spawn { creature }) => {
+                json!({ "ok": false, "error": format!("cannot spawn \"{creature}\"") })
+            }
+            Ok(ControlCommand::Stats) => {
+                let current = &stats.current;
+                json!({
+                    "ok": true,
+                    "fishes": current.fishes,
+                    "snails": current.snails,
+                    "starfish": current.starfish,
+                    "average_age": current.average_age,
+                    "average_satiation": current.average_satiation,
+                    "births": current.births,
+                    "deaths": current.deaths,
+                    "pellets": {
+                        "dispensed": current.pellets.dispensed,
+                        "eaten": current.pellets.eaten,
+                        "rotted": current.pellets.rotted,
+                    },
+                    "temperature": **temperature,
+                    "daytime": **daytime,
+                })
+            }
+            Err(error) => json!({ "ok": false, "error": error }),
+        };
+
+        let _ = request.reply.send(response.to_string());
+    }
+}
+
+/// Sends a single request line to a running aquarium and waits for its reply.
+pub fn send(socket: &Path, request: &str) -> std::io::Result<String> {
+    let mut stream = UnixStream::connect(socket)?;
+    writeln!(stream, "{request}")?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+
+    Ok(response.trim_end().to_string())
+}
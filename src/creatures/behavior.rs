@@ -5,7 +5,11 @@ use rand_chacha::ChaCha8Rng;
 
 use crate::pellets::Pellet;
 
+use super::lifecycle::FishSkeleton;
+
 const BEHAVIOR_INTERVAL_SECONDS: f32 = 8.;
+const BUBBLE_PLAY_LIFT: f32 = 0.25;
+const BUBBLE_PLAY_WOBBLE: f32 = 0.03;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<CreatureRng>();
@@ -19,6 +23,9 @@ pub enum CreatureBehaviorVariant {
     SwimLeft,
     SeekPoint(Vec3),
     SeekPellet(Entity),
+    SeekCarrion(Entity),
+    /// Riding the bubble stream above the stone at this point.
+    PlayInBubbles(Vec3),
 }
 
 #[derive(Component)]
@@ -51,7 +58,8 @@ pub trait CreatureOperations {
         commands: &mut Commands,
         rng: &mut CreatureRng,
         time: &Time,
-        pellets: &Query<(Entity, &mut Transform), (With<Pellet>, Without<CreatureBehavior>)>,
+        pellets: &Query<(Entity, &Transform, &Pellet), Without<CreatureBehavior>>,
+        carrion: &Query<(Entity, &Transform), (With<FishSkeleton>, Without<CreatureBehavior>)>,
     ) {
         match self.behavior().variant {
             CreatureBehaviorVariant::Debut => self.behavior_debut(time, rng),
@@ -62,6 +70,12 @@ pub trait CreatureOperations {
             CreatureBehaviorVariant::SeekPellet(pellet_id) => {
                 self.behavior_seek_pellet(time, rng, pellets.get(pellet_id), commands)
             }
+            CreatureBehaviorVariant::SeekCarrion(carrion_id) => {
+                self.behavior_seek_carrion(time, rng, carrion.get(carrion_id), commands)
+            }
+            CreatureBehaviorVariant::PlayInBubbles(stone) => {
+                self.behavior_play_in_bubbles(time, stone)
+            }
         }
 
         self.clamp();
@@ -73,11 +87,18 @@ pub trait CreatureOperations {
         if self.behavior().timer.just_finished() {
             match self.behavior().variant {
                 CreatureBehaviorVariant::Idle => {
-                    if rng.next_u32() % 9 == 0 {
+                    let roll = rng.next_u32() as f32 / u32::MAX as f32;
+
+                    if roll < self.seek_point_chance() {
                         self.start_seek_point(rng);
                         return;
                     }
 
+                    if roll < self.seek_point_chance() + self.linger_chance() {
+                        self.start_idle();
+                        return;
+                    }
+
                     let current_y = self.transform().rotation.y;
                     self.face_left();
                     if self.transform().rotation.y != current_y {
@@ -88,6 +109,7 @@ pub trait CreatureOperations {
                 }
                 CreatureBehaviorVariant::SwimRight => self.start_idle(),
                 CreatureBehaviorVariant::SwimLeft => self.start_idle(),
+                CreatureBehaviorVariant::PlayInBubbles(_) => self.start_idle(),
                 _ => {}
             }
         }
@@ -95,12 +117,12 @@ pub trait CreatureOperations {
 
     fn detect_pellet(
         &mut self,
-        pellets: &Query<(Entity, &Transform), (With<Pellet>, Without<CreatureBehavior>)>,
+        pellets: &Query<(Entity, &Transform, &Pellet), Without<CreatureBehavior>>,
     ) {
         let (best_pellet_id, best_rank) = pellets.iter().fold(
             (None, f32::MAX),
-            |(best_pellet_id, best_rank), (pellet_id, pellet_transform)| {
-                let rank = self.rank_pellet(pellet_transform);
+            |(best_pellet_id, best_rank), (pellet_id, pellet_transform, pellet)| {
+                let rank = self.rank_pellet(pellet_id, pellet_transform, pellet);
                 if rank < best_rank {
                     (Some(pellet_id), rank)
                 } else {
@@ -116,6 +138,29 @@ pub trait CreatureOperations {
         }
     }
 
+    fn detect_carrion(
+        &mut self,
+        carrion: &Query<(Entity, &Transform), (With<FishSkeleton>, Without<CreatureBehavior>)>,
+    ) {
+        let (best_carrion_id, best_rank) = carrion.iter().fold(
+            (None, f32::MAX),
+            |(best_carrion_id, best_rank), (carrion_id, carrion_transform)| {
+                let rank = self.rank_carrion(carrion_transform);
+                if rank < best_rank {
+                    (Some(carrion_id), rank)
+                } else {
+                    (best_carrion_id, best_rank)
+                }
+            },
+        );
+
+        if let Some(best_carrion_id) = best_carrion_id {
+            if self.check_carrion(best_rank) {
+                self.start_seek_carrion(best_carrion_id);
+            }
+        }
+    }
+
     fn start_idle(&mut self) {
         self.behavior().variant = CreatureBehaviorVariant::Idle;
         self.behavior().timer.reset();
@@ -134,7 +179,7 @@ pub trait CreatureOperations {
     }
 
     fn start_seek_point(&mut self, rng: &mut CreatureRng) {
-        let point = Self::valid_random_point(rng);
+        let point = self.random_point(rng);
 
         if self.transform().translation.x < point.x {
             self.face_right();
@@ -151,11 +196,28 @@ pub trait CreatureOperations {
         self.behavior().timer.reset();
     }
 
+    fn start_seek_carrion(&mut self, carrion_id: Entity) {
+        self.behavior().variant = CreatureBehaviorVariant::SeekCarrion(carrion_id);
+        self.behavior().timer.reset();
+    }
+
+    fn start_play_in_bubbles(&mut self, stone: Vec3) {
+        if self.transform().translation.x < stone.x {
+            self.face_right();
+        } else {
+            self.face_left();
+        }
+
+        self.behavior().variant = CreatureBehaviorVariant::PlayInBubbles(stone);
+        self.behavior().timer.reset();
+    }
+
     fn behavior_debut(&mut self, time: &Time, rng: &mut CreatureRng);
     fn behavior_idle(&mut self, time: &Time);
 
     fn behavior_swim_right(&mut self, time: &Time) {
-        self.transform().translation.x += time.delta_secs() * Self::base_speed();
+        let speed = self.base_speed();
+        self.transform().translation.x += time.delta_secs() * speed;
 
         let (_, max) = Self::valid_area();
         if self.transform().translation.x > max.x {
@@ -164,7 +226,8 @@ pub trait CreatureOperations {
     }
 
     fn behavior_swim_left(&mut self, time: &Time) {
-        self.transform().translation.x -= time.delta_secs() * Self::base_speed();
+        let speed = self.base_speed();
+        self.transform().translation.x -= time.delta_secs() * speed;
 
         let (min, _) = Self::valid_area();
         if self.transform().translation.x < min.x {
@@ -173,26 +236,54 @@ pub trait CreatureOperations {
     }
 
     fn behavior_seek_point(&mut self, time: &Time, target: Vec3) {
+        let speed = self.base_speed();
         self.transform().translation = self
             .transform()
             .translation
-            .move_towards(target, time.delta_secs() * Self::base_speed());
+            .move_towards(target, time.delta_secs() * speed);
 
         if self.transform().translation.distance(target) < 0.1 {
             self.start_idle();
         }
     }
 
+    /// Swims over to the stream and lets it carry them up, bobbing about in the bubbles.
+    fn behavior_play_in_bubbles(&mut self, time: &Time, stone: Vec3) {
+        let speed = self.base_speed();
+        let translation = self.transform().translation;
+        let stream = Vec3::new(stone.x, translation.y, stone.z);
+
+        if translation.distance(stream) > 0.05 {
+            self.transform().translation =
+                translation.move_towards(stream, time.delta_secs() * speed * 1.5);
+            return;
+        }
+
+        let wobble = (time.elapsed_secs() * 6.).sin() * BUBBLE_PLAY_WOBBLE;
+        self.transform().translation.x = stone.x + wobble;
+        self.transform().translation.y += time.delta_secs() * BUBBLE_PLAY_LIFT;
+    }
+
     fn behavior_seek_pellet(
         &mut self,
         _time: &Time,
         _rng: &mut CreatureRng,
-        _pellet: Result<(Entity, &Transform), QueryEntityError>,
+        _pellet: Result<(Entity, &Transform, &Pellet), QueryEntityError>,
         _commands: &mut Commands,
     ) {
         // optional impl
     }
 
+    fn behavior_seek_carrion(
+        &mut self,
+        _time: &Time,
+        _rng: &mut CreatureRng,
+        _carrion: Result<(Entity, &Transform), QueryEntityError>,
+        _commands: &mut Commands,
+    ) {
+        self.start_idle();
+    }
+
     fn face_right(&mut self) {
         // optional impl
     }
@@ -206,7 +297,12 @@ pub trait CreatureOperations {
         self.transform().translation = self.transform().translation.clamp(min, max);
     }
 
-    fn rank_pellet(&mut self, pellet_transform: &Transform) -> f32 {
+    fn rank_pellet(
+        &mut self,
+        _pellet_id: Entity,
+        pellet_transform: &Transform,
+        _pellet: &Pellet,
+    ) -> f32 {
         self.transform()
             .translation
             .distance(pellet_transform.translation)
@@ -216,10 +312,32 @@ pub trait CreatureOperations {
         true
     }
 
+    fn rank_carrion(&mut self, carrion_transform: &Transform) -> f32 {
+        self.transform()
+            .translation
+            .distance(carrion_transform.translation)
+    }
+
+    fn check_carrion(&mut self, _rank: f32) -> bool {
+        false
+    }
+
+    fn seek_point_chance(&mut self) -> f32 {
+        1. / 9.
+    }
+
+    fn linger_chance(&mut self) -> f32 {
+        0.
+    }
+
+    fn random_point(&mut self, rng: &mut CreatureRng) -> Vec3 {
+        Self::valid_random_point(rng)
+    }
+
     fn behavior(&mut self) -> &mut CreatureBehavior;
     fn transform(&mut self) -> &mut Transform;
 
-    fn base_speed() -> f32;
+    fn base_speed(&self) -> f32;
     fn valid_area() -> (Vec3, Vec3);
 
     fn valid_random_point(rng: &mut CreatureRng) -> Vec3 {
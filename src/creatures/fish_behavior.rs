@@ -2,11 +2,12 @@ use std::f32::consts::PI;
 
 use bevy::{ecs::query::QueryEntityError, prelude::*};
 
-use crate::general::AttemptDespawn;
+use crate::{pellets::Pellet, tank::DECORATION_ANCHORS};
 
 use super::{
-    behavior::{CreatureBehavior, CreatureOperations, CreatureRng},
-    lifecycle::FishMortality,
+    behavior::{CreatureBehavior, CreatureBehaviorVariant, CreatureOperations, CreatureRng},
+    lifecycle::{FishFeeding, FishMortality},
+    personality::FishPersonality,
 };
 
 pub(super) const FISH_MAX: usize = 12;
@@ -15,11 +16,24 @@ pub(super) const FISH_AGING_INTERVAL_SECONDS: f32 = 10.;
 pub(super) const FISH_SATIATION_MAX: u32 = 64;
 pub(super) const FISH_AVERAGE_LONGEVITY: u32 = 64;
 pub(super) const FISH_BULK_MAX: u32 = 32;
+pub(super) const FISH_BREEDING_INTERVAL_SECONDS: f32 = 5.;
+pub(super) const FISH_BREEDING_COOLDOWN: u32 = 12;
+pub(super) const FISH_BREEDING_DISTANCE: f32 = 0.4;
+pub(super) const FISH_BREEDING_CHANCE: u32 = 4;
+pub(super) const FISH_BITE_INTERVAL_SECONDS: f32 = 0.4;
+pub(super) const FISH_BITE_NUTRITION: u32 = 2;
+pub(super) const FISH_CONTENTION_DISTANCE: f32 = 0.25;
+pub(super) const FISH_CONTENTION_BULK_MARGIN: u32 = 4;
+pub(super) const FISH_NUDGE_DISTANCE: f32 = 0.15;
 
 pub(super) struct FishOperations<'a> {
     transform: &'a mut Transform,
     behavior: &'a mut CreatureBehavior,
     mortality: &'a mut FishMortality,
+    personality: &'a FishPersonality,
+    feeding: &'a mut FishFeeding,
+    sick: bool,
+    metabolism: f32,
 }
 
 impl<'a> FishOperations<'a> {
@@ -27,18 +41,35 @@ impl<'a> FishOperations<'a> {
         transform: &'a mut Transform,
         behavior: &'a mut CreatureBehavior,
         mortality: &'a mut FishMortality,
+        personality: &'a FishPersonality,
+        feeding: &'a mut FishFeeding,
+        sick: bool,
+        metabolism: f32,
     ) -> Self {
         Self {
             transform,
             behavior,
             mortality,
+            personality,
+            feeding,
+            sick,
+            metabolism,
+        }
+    }
+
+    /// Sick fish lose their energy and appetite.
+    fn vigor(&self) -> f32 {
+        if self.sick {
+            0.4
+        } else {
+            1.
         }
     }
 }
 
 impl CreatureOperations for FishOperations<'_> {
     fn behavior_debut(&mut self, time: &Time, rng: &mut CreatureRng) {
-        self.transform.translation.y += time.delta_secs() * Self::base_speed();
+        self.transform.translation.y += time.delta_secs() * self.base_speed();
 
         if self.transform.translation.y > -0.5 {
             self.start_seek_point(rng);
@@ -53,10 +84,10 @@ impl CreatureOperations for FishOperations<'_> {
         &mut self,
         time: &Time,
         rng: &mut CreatureRng,
-        pellet: Result<(Entity, &Transform), QueryEntityError>,
-        commands: &mut Commands,
+        pellet: Result<(Entity, &Transform, &Pellet), QueryEntityError>,
+        _commands: &mut Commands,
     ) {
-        if let Ok((pellet_entity, pellet_transform)) = pellet {
+        if let Ok((pellet_entity, pellet_transform, pellet)) = pellet {
             if self.mortality.satiation >= FISH_SATIATION_MAX {
                 self.start_seek_point(rng);
                 return;
@@ -70,22 +101,28 @@ impl CreatureOperations for FishOperations<'_> {
 
             self.transform.translation = self.transform.translation.move_towards(
                 pellet_transform.translation,
-                time.delta_secs() * Self::base_speed() * 4.,
+                time.delta_secs()
+                    * self.base_speed()
+                    * (2.5 + 3. * self.personality.greed)
+                    * self.vigor(),
             );
 
+            self.feeding.bite_timer.tick(time.delta());
+
             if self
                 .transform
                 .translation
                 .distance(pellet_transform.translation)
                 < 0.1
+                && self.feeding.bite_timer.finished()
+                && pellet.bites > 0
             {
-                if let Ok(mut entity) = commands.get_entity(pellet_entity) {
-                    self.mortality.satiation += 5;
-                    entity.insert(AttemptDespawn);
-                }
+                self.feeding.bite_timer.reset();
+                self.feeding.bite = Some(pellet_entity);
             }
         } else {
-            self.start_seek_point(rng);
+            // the pellet is gone, so linger until the next detection pass picks another one.
+            self.start_idle();
         }
     }
 
@@ -101,15 +138,55 @@ impl CreatureOperations for FishOperations<'_> {
                 .with_rotation(Quat::from_euler(EulerRot::XYZ, PI / 2., 0., 0.));
     }
 
-    fn rank_pellet(&mut self, pellet_transform: &Transform) -> f32 {
-        self.transform()
+    fn rank_pellet(
+        &mut self,
+        pellet_id: Entity,
+        pellet_transform: &Transform,
+        pellet: &Pellet,
+    ) -> f32 {
+        if self.feeding.spurned == Some(pellet_id) {
+            return f32::MAX;
+        }
+
+        let mut rivals = pellet.claimants;
+        if matches!(self.behavior.variant, CreatureBehaviorVariant::SeekPellet(id) if id == pellet_id)
+        {
+            rivals = rivals.saturating_sub(1);
+        }
+
+        self.transform
             .translation
             .xy()
             .distance(pellet_transform.translation.xy())
+            * (1. + 0.5 * rivals as f32)
     }
 
     fn check_pellet(&mut self, rank: f32) -> bool {
-        rank < 0.8
+        rank < (0.4 + 0.8 * self.personality.greed) * self.vigor()
+    }
+
+    fn seek_point_chance(&mut self) -> f32 {
+        (1. / 18. + 0.2 * self.personality.curiosity) * self.vigor()
+    }
+
+    fn linger_chance(&mut self) -> f32 {
+        if self.sick {
+            0.9
+        } else {
+            0.5 * self.personality.laziness
+        }
+    }
+
+    fn random_point(&mut self, rng: &mut CreatureRng) -> Vec3 {
+        let point = Self::valid_random_point(rng);
+
+        // timid fish keep close to the cover of the nearest decoration.
+        let cover = DECORATION_ANCHORS
+            .into_iter()
+            .min_by(|a, b| a.distance(point).total_cmp(&b.distance(point)))
+            .unwrap_or(point);
+
+        point.lerp(cover, 0.7 * (1. - self.personality.boldness))
     }
 
     fn behavior(&mut self) -> &mut CreatureBehavior {
@@ -120,8 +197,8 @@ impl CreatureOperations for FishOperations<'_> {
         self.transform
     }
 
-    fn base_speed() -> f32 {
-        0.1
+    fn base_speed(&self) -> f32 {
+        0.1 * self.metabolism
     }
 
     fn valid_area() -> (Vec3, Vec3) {
@@ -6,28 +6,53 @@ use bevy::time::common_conditions::on_timer;
 use rand::seq::SliceRandom;
 use rand::RngCore;
 
-use crate::pellets::Pellet;
+use crate::pellets::{Pellet, PelletBiteEvent, PelletEatenEvent};
+use crate::{
+    ambience::SystemLoad, bubbles::BubbleStone, filter::Current, focus::FocusSession,
+    simulation::Interpolated, temperature::WaterTemperature,
+};
 
-use super::behavior::{CreatureBehavior, CreatureOperations, CreatureRng};
-use super::fish_behavior::{FishOperations, FISH_MAX, FISH_SPAWN_INTERVAL_SECONDS};
-use super::lifecycle::{FishMortality, FishSkeleton};
+use super::behavior::{CreatureBehavior, CreatureBehaviorVariant, CreatureOperations, CreatureRng};
+use super::fish_behavior::{
+    FishOperations, FISH_BITE_NUTRITION, FISH_CONTENTION_BULK_MARGIN, FISH_CONTENTION_DISTANCE,
+    FISH_NUDGE_DISTANCE, FISH_SATIATION_MAX,
+};
+use super::illness::Illness;
+use super::lifecycle::{FishFeeding, FishMortality, FishSkeleton};
+use super::personality::FishPersonality;
+use super::starfish_systems::{Starfish, StarfishAttackEvent};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(Startup, setup_fish_system)
         .add_systems(
-            Update,
+            FixedUpdate,
             (
-                populate_fish_system
-                    .run_if(on_timer(Duration::from_secs(FISH_SPAWN_INTERVAL_SECONDS))),
                 fish_spawn_system,
-                fish_behavior_system,
+                pellet_claims_system,
+                fish_contention_system,
+                (fish_behavior_system, fish_bite_system).chain(),
                 fish_behavior_change_system,
                 fish_pellet_detection_system.run_if(on_timer(Duration::from_secs_f32(0.5))),
+                fish_bubble_play_system.run_if(on_timer(Duration::from_secs_f32(1.))),
+                fish_current_effort_system,
+                fish_nip_starfish_system.run_if(on_timer(Duration::from_secs_f32(2.))),
             ),
         )
         .add_event::<FishSpawnEvent>();
 }
 
+pub const FISH_SATURATION: f32 = 0.3;
+/// Chance each second that an idle fish of middling curiosity goes to play in the bubbles.
+const FISH_BUBBLE_PLAY_CHANCE: f32 = 0.04;
+/// Hunger built up per second for each unit of current a fish swims straight into.
+const FISH_CURRENT_EFFORT: f32 = 0.5;
+/// Only fish at least this bold dare to nip at a starfish.
+const FISH_NIP_BOLDNESS: f32 = 0.7;
+const FISH_NIP_DISTANCE: f32 = 0.3;
+/// Fraction of a starfish's arms lost to each nip.
+const FISH_NIP_SEVERITY: f32 = 0.3;
+pub const FISH_EMISSIVE: f32 = 0.3;
+
 #[derive(Component)]
 pub struct Fish;
 
@@ -40,6 +65,13 @@ pub struct FishMaterials(Vec<Handle<StandardMaterial>>);
 #[derive(Event, Deref)]
 pub struct FishSpawnEvent(pub Vec3);
 
+impl FishSpawnEvent {
+    /// A spawn somewhere along the gravel.
+    pub fn random(rng: &mut CreatureRng) -> Self {
+        Self(FishOperations::valid_random_point(rng).with_y(-1.7))
+    }
+}
+
 fn setup_fish_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -58,8 +90,12 @@ fn setup_fish_system(
 
     let fish_materials = (0..36)
         .map(|_| {
-            let base_color = Color::hsl(((rng.next_u32() % 180 + 165) % 360) as f32, 0.3, 0.4);
-            let emissive = base_color.to_linear() * 0.3;
+            let base_color = Color::hsl(
+                ((rng.next_u32() % 180 + 165) % 360) as f32,
+                FISH_SATURATION,
+                0.4,
+            );
+            let emissive = base_color.to_linear() * FISH_EMISSIVE;
 
             materials.add(StandardMaterial {
                 base_color,
@@ -72,24 +108,7 @@ fn setup_fish_system(
 
     commands.insert_resource(FishMaterials(fish_materials));
 
-    spawn_events.write(FishSpawnEvent(
-        FishOperations::valid_random_point(&mut rng).with_y(-1.7),
-    ));
-}
-
-fn populate_fish_system(
-    mut rng: ResMut<CreatureRng>,
-    fishes: Query<Entity, With<Fish>>,
-    skeletons: Query<Entity, With<FishSkeleton>>,
-    mut spawn_events: EventWriter<FishSpawnEvent>,
-) {
-    let fish_count = fishes.iter().len() + skeletons.iter().len();
-
-    if fish_count < FISH_MAX {
-        spawn_events.write(FishSpawnEvent(
-            FishOperations::valid_random_point(&mut rng).with_y(-1.7),
-        ));
-    }
+    spawn_events.write(FishSpawnEvent::random(&mut rng));
 }
 
 fn fish_spawn_system(
@@ -108,6 +127,8 @@ fn fish_spawn_system(
             Fish,
             CreatureBehavior::default(),
             FishMortality::new(&mut rng),
+            FishPersonality::new(&mut rng),
+            FishFeeding::default(),
             transform,
             Mesh3d(fish_mesh.clone()),
             MeshMaterial3d(fish_materials.choose(&mut rng.0).unwrap().clone()),
@@ -118,36 +139,310 @@ fn fish_spawn_system(
 fn fish_behavior_system(
     mut commands: Commands,
     time: Res<Time>,
-    mut fishes: Query<(&mut Transform, &mut CreatureBehavior, &mut FishMortality), With<Fish>>,
-    pellets: Query<(Entity, &mut Transform), (With<Pellet>, Without<CreatureBehavior>)>,
+    temperature: Res<WaterTemperature>,
+    focus: Res<FocusSession>,
+    load: Res<SystemLoad>,
+    mut fishes: Query<
+        (
+            &mut Transform,
+            &mut CreatureBehavior,
+            &mut FishMortality,
+            &FishPersonality,
+            &mut FishFeeding,
+            Has<Illness>,
+        ),
+        With<Fish>,
+    >,
+    pellets: Query<(Entity, &Transform, &Pellet), Without<CreatureBehavior>>,
+    carrion: Query<(Entity, &Transform), (With<FishSkeleton>, Without<CreatureBehavior>)>,
     mut rng: ResMut<CreatureRng>,
 ) {
-    for (mut transform, mut behavior, mut mortality) in fishes.iter_mut() {
-        FishOperations::new(&mut transform, &mut behavior, &mut mortality).do_behavior(
-            &mut commands,
-            &mut rng,
-            &time,
-            &pellets,
-        );
+    for (mut transform, mut behavior, mut mortality, personality, mut feeding, sick) in
+        fishes.iter_mut()
+    {
+        FishOperations::new(
+            &mut transform,
+            &mut behavior,
+            &mut mortality,
+            personality,
+            &mut feeding,
+            sick,
+            fish_metabolism(&temperature, &focus, &load),
+        )
+        .do_behavior(&mut commands, &mut rng, &time, &pellets, &carrion);
+    }
+}
+
+/// Hands out the bites fish took this step one at a time, so that no more fish get something
+/// out of a pellet than it has bites left.
+fn fish_bite_system(
+    mut fishes: Query<(&mut FishMortality, &mut FishFeeding), With<Fish>>,
+    mut pellets: Query<&mut Pellet>,
+    mut bite_events: EventWriter<PelletBiteEvent>,
+    mut eaten_events: EventWriter<PelletEatenEvent>,
+) {
+    for (mut mortality, mut feeding) in fishes.iter_mut() {
+        let Some(pellet_id) = feeding.bite.take() else {
+            continue;
+        };
+        let Ok(mut pellet) = pellets.get_mut(pellet_id) else {
+            continue;
+        };
+        if pellet.bites == 0 {
+            continue;
+        }
+
+        pellet.bites -= 1;
+        feeding.bites += 1;
+        feeding.spurned = None;
+        if pellet.bites == 0 {
+            feeding.pellets_finished += 1;
+            eaten_events.write(PelletEatenEvent(pellet_id));
+        }
+
+        mortality.satiation += FISH_BITE_NUTRITION;
+        bite_events.write(PelletBiteEvent(pellet_id));
     }
 }
 
 fn fish_behavior_change_system(
     time: Res<Time>,
-    mut fishes: Query<(&mut Transform, &mut CreatureBehavior, &mut FishMortality), With<Fish>>,
+    temperature: Res<WaterTemperature>,
+    focus: Res<FocusSession>,
+    load: Res<SystemLoad>,
+    mut fishes: Query<
+        (
+            &mut Transform,
+            &mut CreatureBehavior,
+            &mut FishMortality,
+            &FishPersonality,
+            &mut FishFeeding,
+            Has<Illness>,
+        ),
+        With<Fish>,
+    >,
     mut rng: ResMut<CreatureRng>,
 ) {
-    for (mut transform, mut behavior, mut mortality) in fishes.iter_mut() {
-        FishOperations::new(&mut transform, &mut behavior, &mut mortality)
-            .decide_behavior(&time, &mut rng);
+    for (mut transform, mut behavior, mut mortality, personality, mut feeding, sick) in
+        fishes.iter_mut()
+    {
+        FishOperations::new(
+            &mut transform,
+            &mut behavior,
+            &mut mortality,
+            personality,
+            &mut feeding,
+            sick,
+            fish_metabolism(&temperature, &focus, &load),
+        )
+        .decide_behavior(&time, &mut rng);
     }
 }
 
 fn fish_pellet_detection_system(
-    mut fishes: Query<(&mut Transform, &mut CreatureBehavior, &mut FishMortality), With<Fish>>,
-    pellets: Query<(Entity, &Transform), (With<Pellet>, Without<CreatureBehavior>)>,
+    mut fishes: Query<
+        (
+            &mut Transform,
+            &mut CreatureBehavior,
+            &mut FishMortality,
+            &FishPersonality,
+            &mut FishFeeding,
+            Has<Illness>,
+        ),
+        With<Fish>,
+    >,
+    pellets: Query<(Entity, &Transform, &Pellet), Without<CreatureBehavior>>,
+    temperature: Res<WaterTemperature>,
+    focus: Res<FocusSession>,
+    load: Res<SystemLoad>,
+) {
+    for (mut transform, mut behavior, mut mortality, personality, mut feeding, sick) in
+        fishes.iter_mut()
+    {
+        FishOperations::new(
+            &mut transform,
+            &mut behavior,
+            &mut mortality,
+            personality,
+            &mut feeding,
+            sick,
+            fish_metabolism(&temperature, &focus, &load),
+        )
+        .detect_pellet(&pellets);
+    }
+}
+
+/// How fast fish live right now, with the water temperature, the focus timer and the system
+/// load all speeding them up or slowing them down.
+fn fish_metabolism(temperature: &WaterTemperature, focus: &FocusSession, load: &SystemLoad) -> f32 {
+    temperature.metabolism() * focus.activity() * load.activity()
+}
+
+fn fish_bubble_play_system(
+    mut fishes: Query<
+        (
+            &mut Transform,
+            &mut CreatureBehavior,
+            &mut FishMortality,
+            &FishPersonality,
+            &mut FishFeeding,
+            Has<Illness>,
+        ),
+        With<Fish>,
+    >,
+    stones: Query<&Transform, (With<BubbleStone>, Without<Fish>)>,
+    temperature: Res<WaterTemperature>,
+    focus: Res<FocusSession>,
+    load: Res<SystemLoad>,
+    mut rng: ResMut<CreatureRng>,
+) {
+    let stones: Vec<Vec3> = stones
+        .iter()
+        .map(|transform| transform.translation)
+        .collect();
+    if stones.is_empty() {
+        return;
+    }
+
+    for (mut transform, mut behavior, mut mortality, personality, mut feeding, sick) in
+        fishes.iter_mut()
+    {
+        if !matches!(behavior.variant, CreatureBehaviorVariant::Idle) || sick {
+            continue;
+        }
+
+        let roll = rng.next_u32() as f32 / u32::MAX as f32;
+        if roll >= FISH_BUBBLE_PLAY_CHANCE * (0.5 + personality.curiosity) {
+            continue;
+        }
+
+        let stone = stones[rng.next_u32() as usize % stones.len()];
+        FishOperations::new(
+            &mut transform,
+            &mut behavior,
+            &mut mortality,
+            personality,
+            &mut feeding,
+            sick,
+            fish_metabolism(&temperature, &focus, &load),
+        )
+        .start_play_in_bubbles(stone);
+    }
+}
+
+/// Fish heading into the current work harder and go hungry sooner than those swimming with it.
+fn fish_current_effort_system(
+    time: Res<Time>,
+    current: Res<Current>,
+    mut fishes: Query<(&Transform, &Interpolated, &mut FishMortality), With<Fish>>,
+) {
+    for (transform, interpolated, mut mortality) in fishes.iter_mut() {
+        let heading = interpolated.displacement().normalize_or_zero();
+        let against = (-heading.dot(current.at(transform.translation))).max(0.);
+        mortality.strain(against * FISH_CURRENT_EFFORT * time.delta_secs());
+    }
+}
+
+/// Bold fish on an empty stomach take a nip at any starfish they pass, which then slowly
+/// regrows the lost arm.
+fn fish_nip_starfish_system(
+    fishes: Query<(&Transform, &FishPersonality, &FishMortality), With<Fish>>,
+    starfishes: Query<(Entity, &Transform), (With<Starfish>, Without<Fish>)>,
+    mut attack_events: EventWriter<StarfishAttackEvent>,
+) {
+    for (transform, personality, mortality) in fishes.iter() {
+        if personality.boldness < FISH_NIP_BOLDNESS
+            || mortality.satiation() >= FISH_SATIATION_MAX / 4
+        {
+            continue;
+        }
+
+        for (starfish, starfish_transform) in starfishes.iter() {
+            if transform
+                .translation
+                .distance(starfish_transform.translation)
+                < FISH_NIP_DISTANCE
+            {
+                attack_events.write(StarfishAttackEvent(starfish, FISH_NIP_SEVERITY));
+            }
+        }
+    }
+}
+
+fn pellet_claims_system(
+    fishes: Query<&CreatureBehavior, With<Fish>>,
+    mut pellets: Query<&mut Pellet>,
 ) {
-    for (mut transform, mut behavior, mut mortality) in fishes.iter_mut() {
-        FishOperations::new(&mut transform, &mut behavior, &mut mortality).detect_pellet(&pellets);
+    for mut pellet in pellets.iter_mut() {
+        pellet.claimants = 0;
+    }
+
+    for behavior in fishes.iter() {
+        if let CreatureBehaviorVariant::SeekPellet(pellet_id) = behavior.variant {
+            if let Ok(mut pellet) = pellets.get_mut(pellet_id) {
+                pellet.claimants += 1;
+            }
+        }
+    }
+}
+
+fn fish_contention_system(
+    mut fishes: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut CreatureBehavior,
+            &FishMortality,
+            &mut FishFeeding,
+        ),
+        With<Fish>,
+    >,
+    pellets: Query<&Transform, (With<Pellet>, Without<Fish>)>,
+) {
+    let contenders: Vec<(Entity, Entity, u32, Vec3)> = fishes
+        .iter()
+        .filter_map(|(entity, transform, behavior, mortality, _)| {
+            let CreatureBehaviorVariant::SeekPellet(pellet_id) = behavior.variant else {
+                return None;
+            };
+            let pellet_transform = pellets.get(pellet_id).ok()?;
+
+            (transform.translation.distance(pellet_transform.translation)
+                < FISH_CONTENTION_DISTANCE)
+                .then_some((
+                    entity,
+                    pellet_id,
+                    mortality.bulk(),
+                    pellet_transform.translation,
+                ))
+        })
+        .collect();
+
+    for (entity, pellet_id, bulk, pellet_translation) in contenders.iter() {
+        // a noticeably bigger fish at the same pellet shoulders the smaller one out of the way.
+        let Some((bully, ..)) =
+            contenders
+                .iter()
+                .find(|(other, other_pellet_id, other_bulk, _)| {
+                    other != entity
+                        && other_pellet_id == pellet_id
+                        && *other_bulk >= bulk + FISH_CONTENTION_BULK_MARGIN
+                })
+        else {
+            continue;
+        };
+
+        if let Ok((_, mut transform, mut behavior, _, mut feeding)) = fishes.get_mut(*entity) {
+            let away = (transform.translation - *pellet_translation).normalize_or_zero();
+            transform.translation += away * FISH_NUDGE_DISTANCE;
+            behavior.variant = CreatureBehaviorVariant::Idle;
+            behavior.timer.reset();
+            feeding.spurned = Some(*pellet_id);
+            feeding.nudged_away += 1;
+        }
+
+        if let Ok((.., mut feeding)) = fishes.get_mut(*bully) {
+            feeding.nudges_given += 1;
+        }
     }
 }
@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_hanabi::{
+    Attribute, ColorOverLifetimeModifier, EffectAsset, ExprWriter, Gradient, ParticleEffect,
+    SetAttributeModifier, SetPositionSphereModifier, ShapeDimension, SpawnerSettings,
+};
+use rand::RngCore;
+
+use crate::{
+    pellets::{Pellet, Perishable},
+    temperature::WaterTemperature,
+};
+
+use super::{
+    behavior::CreatureRng, fish_behavior::FISH_SATIATION_MAX, fish_systems::Fish,
+    lifecycle::FishMortality, population::PopulationConfig,
+};
+
+pub(super) const ILLNESS_LONGEVITY_TOLL: u32 = 2;
+const ILLNESS_CHECK_INTERVAL_SECONDS: f32 = 5.;
+const ILLNESS_BASE_CHANCE: f32 = 0.002;
+const ILLNESS_HUNGER_CHANCE: f32 = 0.02;
+const ILLNESS_CROWDING_CHANCE: f32 = 0.02;
+const ILLNESS_ROT_CHANCE: f32 = 0.01;
+const ILLNESS_STRESS_CHANCE: f32 = 0.03;
+const ILLNESS_SPREAD_CHANCE: f32 = 0.08;
+const ILLNESS_SPREAD_DISTANCE: f32 = 0.35;
+const ILLNESS_RECOVERY_CHANCE: f32 = 0.02;
+const ILLNESS_MEDICATED_RECOVERY_CHANCE: f32 = 0.3;
+const MEDICATION_DURATION_SECONDS: f32 = 60.;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, setup_illness_system)
+        .add_systems(
+            Update,
+            (
+                medicate_system,
+                illness_outbreak_system.run_if(on_timer(Duration::from_secs_f32(
+                    ILLNESS_CHECK_INTERVAL_SECONDS,
+                ))),
+                illness_spots_system,
+            )
+                .chain(),
+        )
+        .init_resource::<Medication>()
+        .add_event::<MedicateEvent>();
+}
+
+/// A sick fish, remembering its healthy colouring for when it recovers.
+#[derive(Component)]
+pub struct Illness {
+    original_material: Handle<StandardMaterial>,
+}
+
+/// White-spot particles following the sick fish they belong to.
+#[derive(Component, Deref)]
+pub struct IllnessSpots(Entity);
+
+#[derive(Resource, Deref)]
+pub struct IllnessSpotsEffect(Handle<EffectAsset>);
+
+/// Medication dissolved in the water, which halts outbreaks and helps sick fish recover.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Medication(Option<Timer>);
+
+impl Medication {
+    pub fn is_active(&self) -> bool {
+        self.is_some()
+    }
+}
+
+#[derive(Event, Default)]
+pub struct MedicateEvent;
+
+fn setup_illness_system(mut commands: Commands, mut effects: ResMut<Assets<EffectAsset>>) {
+    let writer = ExprWriter::new();
+
+    let init_size = SetAttributeModifier::new(Attribute::SIZE, writer.lit(0.015).expr());
+
+    let init_age = SetAttributeModifier::new(Attribute::AGE, writer.lit(0.).expr());
+
+    let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, writer.lit(0.8).expr());
+
+    let init_pos = SetPositionSphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        radius: writer.lit(0.06).expr(),
+        dimension: ShapeDimension::Volume,
+    };
+
+    let mut color_gradient = Gradient::new();
+    color_gradient.add_key(0.0, Vec4::new(1.0, 1.0, 1.0, 0.9));
+    color_gradient.add_key(1.0, Vec4::new(1.0, 1.0, 1.0, 0.0));
+
+    let update_color = ColorOverLifetimeModifier {
+        gradient: color_gradient,
+        ..default()
+    };
+
+    let spots_effect = effects.add(
+        EffectAsset::new(4096, SpawnerSettings::rate(4.0.into()), writer.finish())
+            .init(init_size)
+            .init(init_pos)
+            .init(init_age)
+            .init(init_lifetime)
+            .render(update_color),
+    );
+
+    commands.insert_resource(IllnessSpotsEffect(spots_effect));
+}
+
+fn medicate_system(
+    time: Res<Time>,
+    mut medicate_events: EventReader<MedicateEvent>,
+    mut medication: ResMut<Medication>,
+) {
+    for _ in medicate_events.read() {
+        **medication = Some(Timer::from_seconds(
+            MEDICATION_DURATION_SECONDS,
+            TimerMode::Once,
+        ));
+    }
+
+    if (**medication)
+        .as_mut()
+        .is_some_and(|timer| timer.tick(time.delta()).finished())
+    {
+        **medication = None;
+    }
+}
+
+fn illness_outbreak_system(
+    mut commands: Commands,
+    mut rng: ResMut<CreatureRng>,
+    medication: Res<Medication>,
+    fishes: Query<
+        (
+            Entity,
+            &Transform,
+            &FishMortality,
+            &MeshMaterial3d<StandardMaterial>,
+            Option<&Illness>,
+        ),
+        With<Fish>,
+    >,
+    pellets: Query<&Perishable, With<Pellet>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    spots_effect: Res<IllnessSpotsEffect>,
+    temperature: Res<WaterTemperature>,
+    population: Res<PopulationConfig>,
+) {
+    let mut roll = || rng.next_u32() as f32 / u32::MAX as f32;
+
+    let crowding =
+        ((fishes.iter().len() as f32 / population.capacity() as f32 - 0.75) * 4.).clamp(0., 1.);
+    let rotting = pellets
+        .iter()
+        .filter(|perishable| perishable.fraction() > 0.5)
+        .count();
+
+    let sick: Vec<Vec3> = fishes
+        .iter()
+        .filter(|(.., illness)| illness.is_some())
+        .map(|(_, transform, ..)| transform.translation)
+        .collect();
+
+    for (entity, transform, mortality, material, illness) in fishes.iter() {
+        if let Some(illness) = illness {
+            let recovery_chance = if medication.is_active() {
+                ILLNESS_MEDICATED_RECOVERY_CHANCE
+            } else if mortality.satiation() >= FISH_SATIATION_MAX / 2 {
+                ILLNESS_RECOVERY_CHANCE
+            } else {
+                0.
+            };
+
+            if roll() < recovery_chance {
+                commands
+                    .entity(entity)
+                    .remove::<Illness>()
+                    .insert(MeshMaterial3d(illness.original_material.clone()));
+            }
+
+            continue;
+        }
+
+        if medication.is_active() {
+            continue;
+        }
+
+        let mut chance = ILLNESS_BASE_CHANCE
+            + ILLNESS_CROWDING_CHANCE * crowding
+            + ILLNESS_ROT_CHANCE * rotting as f32
+            + ILLNESS_STRESS_CHANCE * temperature.stress();
+
+        if mortality.satiation() < FISH_SATIATION_MAX / 4 {
+            chance += ILLNESS_HUNGER_CHANCE;
+        }
+
+        chance += ILLNESS_SPREAD_CHANCE
+            * sick
+                .iter()
+                .filter(|other| other.distance(transform.translation) < ILLNESS_SPREAD_DISTANCE)
+                .count() as f32;
+
+        if roll() >= chance {
+            continue;
+        }
+
+        let Some(mut symptoms) = materials.get(&material.0).cloned() else {
+            continue;
+        };
+
+        let mut hsla = Hsla::from(symptoms.base_color);
+        hsla.saturation *= 0.2;
+        symptoms.base_color = hsla.into();
+        symptoms.emissive = symptoms.base_color.to_linear() * 0.3;
+
+        commands.entity(entity).insert((
+            Illness {
+                original_material: material.0.clone(),
+            },
+            MeshMaterial3d(materials.add(symptoms)),
+        ));
+        commands.spawn((
+            IllnessSpots(entity),
+            ParticleEffect::new(spots_effect.clone()),
+            Transform::from_translation(transform.translation),
+        ));
+    }
+}
+
+fn illness_spots_system(
+    mut commands: Commands,
+    mut spots_query: Query<(Entity, &IllnessSpots, &mut Transform)>,
+    hosts: Query<&Transform, (With<Illness>, Without<IllnessSpots>)>,
+) {
+    for (entity, spots, mut transform) in spots_query.iter_mut() {
+        match hosts.get(**spots) {
+            Ok(host_transform) => transform.translation = host_transform.translation,
+            Err(_) => commands.entity(entity).despawn(),
+        }
+    }
+}
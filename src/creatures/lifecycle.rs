@@ -3,17 +3,43 @@ use std::f32::consts::PI;
 use bevy::prelude::*;
 use rand::RngCore;
 
+use crate::{algae::Nutrients, temperature::WaterTemperature};
+
 use super::{
     behavior::CreatureRng,
     fish_behavior::{
-        FISH_AGING_INTERVAL_SECONDS, FISH_AVERAGE_LONGEVITY, FISH_BULK_MAX, FISH_SATIATION_MAX,
+        FISH_AGING_INTERVAL_SECONDS, FISH_AVERAGE_LONGEVITY, FISH_BITE_INTERVAL_SECONDS,
+        FISH_BREEDING_COOLDOWN, FISH_BULK_MAX, FISH_SATIATION_MAX,
+    },
+    illness::{Illness, ILLNESS_LONGEVITY_TOLL},
+    snail_systems::Snail,
+    starfish_behavior::{
+        STARFISH_AGING_INTERVAL_SECONDS, STARFISH_AVERAGE_LONGEVITY, STARFISH_FRAGMENT_SIZE,
+        STARFISH_GROWTH_RATE, STARFISH_MIN, STARFISH_REGROWTH_RATE, STARFISH_SATIATION_MAX,
+        STARFISH_SIZE_MAX,
     },
-    fish_systems::FishSpawnEvent,
+    starfish_systems::{Starfish, StarfishAttackEvent, StarfishSpawnEvent},
 };
 
+const SKELETON_REST_Y: f32 = -1.78;
+const SKELETON_BURIED_Y: f32 = -1.9;
+const SKELETON_REMAINS: u32 = 32;
+const SKELETON_DECOMPOSE_INTERVAL_SECONDS: f32 = 2.;
+const SKELETON_SCAVENGE_RADIUS: f32 = 0.3;
+
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(Startup, setup_lifecycle_system)
-        .add_systems(Update, (age_the_living_system, fish_skeleton_system));
+        .add_systems(
+            FixedUpdate,
+            (
+                age_the_living_system,
+                age_the_starfish_system,
+                starfish_attack_system,
+                fish_skeleton_system,
+                decompose_skeletons_system,
+            ),
+        )
+        .init_resource::<DeathRecord>();
 }
 
 #[derive(Component)]
@@ -21,8 +47,12 @@ pub struct FishMortality {
     next_age_timer: Timer,
     age: u32,
     pub(super) satiation: u32,
+    hunger: f32,
     bulk: u32,
     longevity: u32,
+    /// How much of its longevity the fish has lost to illness.
+    lost_to_illness: u32,
+    last_bred: u32,
 }
 
 impl FishMortality {
@@ -31,8 +61,96 @@ impl FishMortality {
             next_age_timer: Timer::from_seconds(FISH_AGING_INTERVAL_SECONDS, TimerMode::Repeating),
             age: 0,
             satiation: FISH_SATIATION_MAX / 2,
+            hunger: 0.,
             bulk: 0,
             longevity: FISH_AVERAGE_LONGEVITY + rng.next_u32() % 32 - 16,
+            lost_to_illness: 0,
+            last_bred: 0,
+        }
+    }
+
+    /// Adds to the hunger that builds up towards the next aging tick.
+    pub(super) fn strain(&mut self, effort: f32) {
+        self.hunger += effort;
+    }
+
+    pub fn age(&self) -> u32 {
+        self.age
+    }
+
+    pub fn satiation(&self) -> u32 {
+        self.satiation
+    }
+
+    pub fn longevity(&self) -> u32 {
+        self.longevity
+    }
+
+    pub fn bulk(&self) -> u32 {
+        self.bulk
+    }
+
+    pub(super) fn can_breed(&self) -> bool {
+        self.age - self.last_bred > FISH_BREEDING_COOLDOWN
+            && self.bulk >= FISH_BULK_MAX / 2
+            && self.satiation >= FISH_SATIATION_MAX * 3 / 4
+    }
+
+    pub(super) fn breed(&mut self) {
+        self.last_bred = self.age;
+        self.satiation -= FISH_SATIATION_MAX / 4;
+    }
+}
+
+/// Running tally of how a fish has fared at feeding time.
+#[derive(Component)]
+pub struct FishFeeding {
+    pub(super) bite_timer: Timer,
+    pub(super) spurned: Option<Entity>,
+    /// The pellet bitten into this step, which only counts if it has a bite left.
+    pub(super) bite: Option<Entity>,
+    pub bites: u32,
+    pub pellets_finished: u32,
+    pub nudges_given: u32,
+    pub nudged_away: u32,
+}
+
+impl Default for FishFeeding {
+    fn default() -> Self {
+        Self {
+            bite_timer: Timer::from_seconds(FISH_BITE_INTERVAL_SECONDS, TimerMode::Once),
+            spurned: None,
+            bite: None,
+            bites: 0,
+            pellets_finished: 0,
+            nudges_given: 0,
+            nudged_away: 0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct StarfishMortality {
+    next_age_timer: Timer,
+    age: u32,
+    pub(super) satiation: u32,
+    longevity: u32,
+    pub(super) size: f32,
+    pub(super) regrowth: f32,
+}
+
+impl StarfishMortality {
+    pub fn new(rng: &mut CreatureRng, size: f32) -> Self {
+        Self {
+            next_age_timer: Timer::from_seconds(
+                STARFISH_AGING_INTERVAL_SECONDS,
+                TimerMode::Repeating,
+            ),
+            age: 0,
+            satiation: STARFISH_SATIATION_MAX / 2,
+            longevity: STARFISH_AVERAGE_LONGEVITY + rng.next_u32() % 64 - 32,
+            size,
+            regrowth: 1.,
         }
     }
 }
@@ -40,6 +158,48 @@ impl FishMortality {
 #[derive(Component)]
 pub struct FishSkeleton;
 
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CauseOfDeath {
+    OldAge,
+    Starvation,
+    Illness,
+}
+
+/// Tally of fish deaths in the tank, by cause.
+#[derive(Resource, Default)]
+pub struct DeathRecord {
+    pub old_age: u32,
+    pub starvation: u32,
+    pub illness: u32,
+}
+
+impl DeathRecord {
+    fn record(&mut self, cause: CauseOfDeath) {
+        match cause {
+            CauseOfDeath::OldAge => self.old_age += 1,
+            CauseOfDeath::Starvation => self.starvation += 1,
+            CauseOfDeath::Illness => self.illness += 1,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Decomposing {
+    timer: Timer,
+    remains: u32,
+    scale: Vec3,
+}
+
+impl Decomposing {
+    fn new(scale: Vec3) -> Self {
+        Self {
+            timer: Timer::from_seconds(SKELETON_DECOMPOSE_INTERVAL_SECONDS, TimerMode::Repeating),
+            remains: SKELETON_REMAINS,
+            scale,
+        }
+    }
+}
+
 #[derive(Resource, Deref)]
 pub struct FishSkeletonScene(SceneRoot);
 
@@ -54,26 +214,50 @@ fn setup_lifecycle_system(mut commands: Commands, asset_server: Res<AssetServer>
 fn age_the_living_system(
     mut commands: Commands,
     time: Res<Time>,
-    mut living_query: Query<(Entity, &mut FishMortality, &mut Transform)>,
+    mut living_query: Query<(Entity, &mut FishMortality, &mut Transform, Has<Illness>)>,
     fish_skeleton: Res<FishSkeletonScene>,
+    mut death_record: ResMut<DeathRecord>,
+    temperature: Res<WaterTemperature>,
 ) {
-    for (entity, mut mortality, mut transform) in living_query.iter_mut() {
+    for (entity, mut mortality, mut transform, sick) in living_query.iter_mut() {
         mortality.next_age_timer.tick(time.delta());
 
         if mortality.next_age_timer.finished() {
             mortality.next_age_timer.reset();
 
             mortality.age += 1;
-            mortality.satiation -= 1;
+
+            // warmer water burns through food faster, colder water slower.
+            mortality.hunger += temperature.metabolism();
+            let hunger = mortality.hunger.floor();
+            mortality.hunger -= hunger;
+            mortality.satiation = mortality.satiation.saturating_sub(hunger as u32);
 
             if mortality.satiation > FISH_SATIATION_MAX / 2 && mortality.bulk < FISH_BULK_MAX {
                 mortality.bulk += 1;
             }
 
+            if sick {
+                let toll = ILLNESS_LONGEVITY_TOLL.min(mortality.longevity);
+                mortality.longevity -= toll;
+                mortality.lost_to_illness += toll;
+            }
+
             if mortality.satiation == 0 || mortality.age > mortality.longevity {
+                // illness only takes the blame when the lifespan it took away is what ran out.
+                let cause = if mortality.satiation == 0 {
+                    CauseOfDeath::Starvation
+                } else if mortality.age <= mortality.longevity + mortality.lost_to_illness {
+                    CauseOfDeath::Illness
+                } else {
+                    CauseOfDeath::OldAge
+                };
+                death_record.record(cause);
+
                 commands.entity(entity).despawn();
                 commands.spawn((
                     FishSkeleton,
+                    cause,
                     fish_skeleton.clone(),
                     transform.with_rotation(Quat::from_rotation_x(PI)),
                 ));
@@ -89,18 +273,129 @@ fn age_the_living_system(
     }
 }
 
+fn age_the_starfish_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut starfish_query: Query<(Entity, &mut StarfishMortality, &Transform)>,
+    mut spawn_events: EventWriter<StarfishSpawnEvent>,
+) {
+    let mut starfish_count = starfish_query.iter().len();
+
+    for (entity, mut mortality, transform) in starfish_query.iter_mut() {
+        mortality.next_age_timer.tick(time.delta());
+
+        if !mortality.next_age_timer.finished() {
+            continue;
+        }
+
+        mortality.next_age_timer.reset();
+
+        mortality.age += 1;
+        mortality.satiation = mortality.satiation.saturating_sub(1);
+
+        if mortality.satiation > STARFISH_SATIATION_MAX / 4 {
+            if mortality.regrowth < 1. {
+                mortality.regrowth = (mortality.regrowth + STARFISH_REGROWTH_RATE).min(1.);
+                mortality.satiation -= 1;
+            } else if mortality.size < STARFISH_SIZE_MAX {
+                mortality.size = (mortality.size + STARFISH_GROWTH_RATE).min(STARFISH_SIZE_MAX);
+            }
+        }
+
+        if mortality.satiation == 0 || mortality.age > mortality.longevity {
+            commands.entity(entity).despawn();
+            starfish_count -= 1;
+            continue;
+        }
+
+        // a well-fed starfish in a sparse tank sheds an arm, which regrows into a new starfish.
+        if starfish_count < STARFISH_MIN
+            && mortality.regrowth >= 1.
+            && mortality.satiation > STARFISH_SATIATION_MAX / 2
+        {
+            mortality.regrowth = 0.8;
+            mortality.satiation -= STARFISH_SATIATION_MAX / 4;
+            spawn_events.write(StarfishSpawnEvent(
+                transform.translation,
+                STARFISH_FRAGMENT_SIZE,
+            ));
+            starfish_count += 1;
+        }
+    }
+}
+
+fn starfish_attack_system(
+    mut attack_events: EventReader<StarfishAttackEvent>,
+    mut starfish_query: Query<&mut StarfishMortality>,
+) {
+    for StarfishAttackEvent(entity, severity) in attack_events.read() {
+        if let Ok(mut mortality) = starfish_query.get_mut(*entity) {
+            mortality.regrowth = (mortality.regrowth - severity).max(0.2);
+        }
+    }
+}
+
 fn fish_skeleton_system(
     time: Res<Time>,
     mut commands: Commands,
-    mut skeleton_query: Query<(Entity, &mut Transform), With<FishSkeleton>>,
-    mut spawn_events: EventWriter<FishSpawnEvent>,
+    mut skeleton_query: Query<(Entity, &mut Transform), (With<FishSkeleton>, Without<Decomposing>)>,
 ) {
     for (entity, mut transform) in skeleton_query.iter_mut() {
         transform.translation.y -= time.delta_secs() / 10.;
 
-        if transform.translation.y < -1.9 {
-            commands.entity(entity).despawn();
-            spawn_events.write(FishSpawnEvent(transform.translation));
+        if transform.translation.y < SKELETON_REST_Y {
+            transform.translation.y = SKELETON_REST_Y;
+            commands
+                .entity(entity)
+                .insert(Decomposing::new(transform.scale));
         }
     }
 }
+
+fn decompose_skeletons_system(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut skeleton_query: Query<(Entity, &mut Decomposing, &mut Transform), With<FishSkeleton>>,
+    mut scavenger_query: Query<
+        (&Transform, Option<&mut StarfishMortality>),
+        (Or<(With<Snail>, With<Starfish>)>, Without<FishSkeleton>),
+    >,
+    mut nutrients: ResMut<Nutrients>,
+) {
+    for (entity, mut decomposing, mut transform) in skeleton_query.iter_mut() {
+        decomposing.timer.tick(time.delta());
+
+        if decomposing.timer.just_finished() {
+            let mut decay = 1;
+
+            for (scavenger_transform, mortality) in scavenger_query.iter_mut() {
+                if scavenger_transform
+                    .translation
+                    .distance(transform.translation)
+                    > SKELETON_SCAVENGE_RADIUS
+                {
+                    continue;
+                }
+
+                decay += 1;
+
+                if let Some(mut mortality) = mortality {
+                    mortality.satiation = (mortality.satiation + 1).min(STARFISH_SATIATION_MAX);
+                }
+            }
+
+            decomposing.remains = decomposing.remains.saturating_sub(decay);
+            **nutrients += decay as f32;
+
+            if decomposing.remains == 0 {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        }
+
+        let remaining = decomposing.remains as f32 / SKELETON_REMAINS as f32;
+        transform.scale = decomposing.scale * (0.3 + 0.7 * remaining);
+        transform.translation.y =
+            SKELETON_BURIED_Y + (SKELETON_REST_Y - SKELETON_BURIED_Y) * remaining;
+    }
+}
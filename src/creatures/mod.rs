@@ -1,19 +1,25 @@
 use bevy::prelude::*;
 
-mod behavior;
+pub mod behavior;
 mod fish_behavior;
-mod fish_systems;
-mod lifecycle;
+pub mod fish_systems;
+pub mod illness;
+pub mod lifecycle;
+pub mod personality;
+pub mod population;
 mod snail_behavior;
-mod snail_systems;
+pub mod snail_systems;
 mod starfish_behavior;
-mod starfish_systems;
+pub mod starfish_systems;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         behavior::plugin,
         fish_systems::plugin,
+        illness::plugin,
         lifecycle::plugin,
+        personality::plugin,
+        population::plugin,
         snail_systems::plugin,
         starfish_systems::plugin,
     ));
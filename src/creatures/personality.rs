@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use rand::RngCore;
+
+use super::{behavior::CreatureRng, fish_systems::Fish};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, inspect_fish_system)
+        .init_resource::<Inspector>()
+        .add_event::<InspectEvent>();
+}
+
+/// Temperament of an individual fish, each trait ranging from zero to one.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FishPersonality {
+    /// How far bold fish stray from the cover of the decorations.
+    pub boldness: f32,
+    /// How often curious fish set out to explore a new spot.
+    pub curiosity: f32,
+    /// How often lazy fish stay put instead of swimming.
+    pub laziness: f32,
+    /// How eagerly greedy fish chase down pellets.
+    pub greed: f32,
+}
+
+impl FishPersonality {
+    pub fn new(rng: &mut CreatureRng) -> Self {
+        let mut roll = || rng.next_u32() as f32 / u32::MAX as f32;
+
+        Self {
+            boldness: roll(),
+            curiosity: roll(),
+            laziness: roll(),
+            greed: roll(),
+        }
+    }
+}
+
+/// The fish currently shown in the inspector panel of the debug overlay.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Inspector(pub Option<Entity>);
+
+#[derive(Event, Default)]
+pub struct InspectEvent;
+
+fn inspect_fish_system(
+    mut inspect_events: EventReader<InspectEvent>,
+    mut inspector: ResMut<Inspector>,
+    fishes: Query<Entity, With<Fish>>,
+) {
+    if inspector.is_some_and(|entity| !fishes.contains(entity)) {
+        **inspector = None;
+    }
+
+    for _ in inspect_events.read() {
+        let mut entities: Vec<Entity> = fishes.iter().collect();
+        entities.sort();
+
+        **inspector = match **inspector {
+            Some(current) => entities.into_iter().find(|entity| *entity > current),
+            None => entities.first().copied(),
+        };
+    }
+}
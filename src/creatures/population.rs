@@ -0,0 +1,370 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use rand::RngCore;
+use serde::Deserialize;
+
+use crate::{config::config_section, temperature::WaterTemperature};
+
+use super::{
+    behavior::{CreatureOperations, CreatureRng},
+    fish_behavior::{
+        FishOperations, FISH_BREEDING_CHANCE, FISH_BREEDING_DISTANCE,
+        FISH_BREEDING_INTERVAL_SECONDS, FISH_MAX, FISH_SPAWN_INTERVAL_SECONDS,
+    },
+    fish_systems::{Fish, FishSpawnEvent},
+    lifecycle::{FishMortality, FishSkeleton},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    let config: PopulationConfig = config_section(app, "population");
+
+    app.add_systems(Update, change_population_policy_system)
+        .add_systems(
+            FixedUpdate,
+            (
+                populate_fish_system,
+                fish_breeding_system.run_if(on_timer(Duration::from_secs_f32(
+                    FISH_BREEDING_INTERVAL_SECONDS,
+                ))),
+            )
+                .chain(),
+        )
+        .insert_resource(Population(config.policy.policy(&config)))
+        .insert_resource(config)
+        .add_event::<PopulationPolicyEvent>()
+        .add_event::<FishBirthEvent>();
+}
+
+/// The `[population]` table of the config file, which every policy takes its parameters from.
+#[derive(Resource, Deserialize)]
+#[serde(default)]
+pub struct PopulationConfig {
+    /// The policy the tank starts out with.
+    policy: PopulationPolicyKind,
+    /// Most fish the tank holds, counting remains for the fixed policy.
+    capacity: usize,
+    /// Seconds between arrivals for the fixed and endless policies.
+    interval: f32,
+    immigrant_rate_per_minute: f32,
+    endless_target: usize,
+}
+
+impl Default for PopulationConfig {
+    fn default() -> Self {
+        Self {
+            policy: PopulationPolicyKind::default(),
+            capacity: FISH_MAX,
+            interval: FISH_SPAWN_INTERVAL_SECONDS as f32,
+            immigrant_rate_per_minute: 2.,
+            endless_target: 8,
+        }
+    }
+}
+
+impl PopulationConfig {
+    fn interval(&self) -> Timer {
+        Timer::from_seconds(self.interval.max(0.1), TimerMode::Repeating)
+    }
+
+    /// Most fish the tank holds, never less than one.
+    pub fn capacity(&self) -> usize {
+        self.capacity.max(1)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PopulationPolicyKind {
+    #[default]
+    Fixed,
+    Natural,
+    Immigrant,
+    Endless,
+}
+
+impl PopulationPolicyKind {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Fixed => Self::Natural,
+            Self::Natural => Self::Immigrant,
+            Self::Immigrant => Self::Endless,
+            Self::Endless => Self::Fixed,
+        }
+    }
+
+    pub fn policy(self, config: &PopulationConfig) -> Box<dyn PopulationPolicy> {
+        match self {
+            Self::Fixed => Box::new(FixedPolicy {
+                max: config.capacity,
+                interval: config.interval(),
+            }),
+            Self::Natural => Box::new(NaturalPolicy {
+                capacity: config.capacity,
+            }),
+            Self::Immigrant => Box::new(ImmigrantPolicy {
+                rate_per_minute: config.immigrant_rate_per_minute.max(0.),
+                capacity: config.capacity,
+            }),
+            Self::Endless => Box::new(EndlessPolicy {
+                target: config.endless_target,
+                interval: config.interval(),
+            }),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Fixed => "fixed",
+            Self::Natural => "natural",
+            Self::Immigrant => "immigrant",
+            Self::Endless => "endless",
+        }
+    }
+}
+
+/// A fish born to a breeding pair, as opposed to one stocked or arriving from outside.
+#[derive(Event, Deref)]
+pub struct FishBirthEvent(pub Vec3);
+
+#[derive(Event)]
+pub enum PopulationPolicyEvent {
+    Next,
+    Select(PopulationPolicyKind),
+}
+
+/// A head count of the tank, handed to the population policy every tick.
+pub struct Census {
+    pub fishes: usize,
+    pub remains: usize,
+}
+
+/// Decides when new fish enter the tank, either as arrivals from outside or as births.
+pub trait PopulationPolicy: Send + Sync {
+    fn kind(&self) -> PopulationPolicyKind;
+
+    /// Number of fish arriving from outside the tank during this tick.
+    fn arrivals(&mut self, time: &Time, census: &Census, rng: &mut CreatureRng) -> usize;
+
+    fn allows_breeding(&self, census: &Census) -> bool;
+
+    /// Human-readable summary of the tunable parameters, for the debug overlay.
+    fn parameters(&self) -> String;
+}
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct Population(pub Box<dyn PopulationPolicy>);
+
+/// Tops the tank up to a fixed number of fish, counting remains that have yet to decompose.
+pub struct FixedPolicy {
+    pub max: usize,
+    pub interval: Timer,
+}
+
+impl PopulationPolicy for FixedPolicy {
+    fn kind(&self) -> PopulationPolicyKind {
+        PopulationPolicyKind::Fixed
+    }
+
+    fn arrivals(&mut self, time: &Time, census: &Census, _rng: &mut CreatureRng) -> usize {
+        self.interval.tick(time.delta());
+
+        if self.interval.just_finished() && census.fishes + census.remains < self.max {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn allows_breeding(&self, _census: &Census) -> bool {
+        false
+    }
+
+    fn parameters(&self) -> String {
+        format!(
+            "max {}, every {}s",
+            self.max,
+            self.interval.duration().as_secs_f32()
+        )
+    }
+}
+
+/// No fish arrive from outside, the population only grows when fish breed.
+pub struct NaturalPolicy {
+    pub capacity: usize,
+}
+
+impl PopulationPolicy for NaturalPolicy {
+    fn kind(&self) -> PopulationPolicyKind {
+        PopulationPolicyKind::Natural
+    }
+
+    fn arrivals(&mut self, _time: &Time, _census: &Census, _rng: &mut CreatureRng) -> usize {
+        0
+    }
+
+    fn allows_breeding(&self, census: &Census) -> bool {
+        census.fishes < self.capacity
+    }
+
+    fn parameters(&self) -> String {
+        format!("capacity {}", self.capacity)
+    }
+}
+
+/// Fish wander in at random at an average rate, on top of breeding.
+pub struct ImmigrantPolicy {
+    pub rate_per_minute: f32,
+    pub capacity: usize,
+}
+
+impl PopulationPolicy for ImmigrantPolicy {
+    fn kind(&self) -> PopulationPolicyKind {
+        PopulationPolicyKind::Immigrant
+    }
+
+    fn arrivals(&mut self, time: &Time, census: &Census, rng: &mut CreatureRng) -> usize {
+        let chance = self.rate_per_minute / 60. * time.delta_secs();
+        let roll = rng.next_u32() as f32 / u32::MAX as f32;
+
+        if census.fishes < self.capacity && roll < chance {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn allows_breeding(&self, census: &Census) -> bool {
+        census.fishes < self.capacity
+    }
+
+    fn parameters(&self) -> String {
+        format!("{}/min, capacity {}", self.rate_per_minute, self.capacity)
+    }
+}
+
+/// Keeps the tank near a target population, restocking faster the further below it falls.
+pub struct EndlessPolicy {
+    pub target: usize,
+    pub interval: Timer,
+}
+
+impl PopulationPolicy for EndlessPolicy {
+    fn kind(&self) -> PopulationPolicyKind {
+        PopulationPolicyKind::Endless
+    }
+
+    fn arrivals(&mut self, time: &Time, census: &Census, _rng: &mut CreatureRng) -> usize {
+        let deficit = self.target.saturating_sub(census.fishes);
+        self.interval.tick(time.delta() * deficit.max(1) as u32);
+
+        if self.interval.just_finished() && deficit > 0 {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn allows_breeding(&self, census: &Census) -> bool {
+        census.fishes < self.target
+    }
+
+    fn parameters(&self) -> String {
+        format!(
+            "target {}, every {}s",
+            self.target,
+            self.interval.duration().as_secs_f32()
+        )
+    }
+}
+
+fn change_population_policy_system(
+    mut policy_events: EventReader<PopulationPolicyEvent>,
+    mut population: ResMut<Population>,
+    config: Res<PopulationConfig>,
+) {
+    for event in policy_events.read() {
+        let kind = match event {
+            PopulationPolicyEvent::Next => population.kind().next(),
+            PopulationPolicyEvent::Select(kind) => *kind,
+        };
+
+        if kind != population.kind() {
+            **population = kind.policy(&config);
+        }
+    }
+}
+
+fn populate_fish_system(
+    time: Res<Time>,
+    mut rng: ResMut<CreatureRng>,
+    mut population: ResMut<Population>,
+    fishes: Query<(), With<Fish>>,
+    skeletons: Query<(), With<FishSkeleton>>,
+    mut spawn_events: EventWriter<FishSpawnEvent>,
+) {
+    let census = Census {
+        fishes: fishes.iter().len(),
+        remains: skeletons.iter().len(),
+    };
+
+    for _ in 0..population.arrivals(&time, &census, &mut rng) {
+        spawn_events.write(FishSpawnEvent(
+            FishOperations::valid_random_point(&mut rng).with_y(-1.7),
+        ));
+    }
+}
+
+fn fish_breeding_system(
+    mut rng: ResMut<CreatureRng>,
+    population: Res<Population>,
+    mut fishes: Query<(Entity, &Transform, &mut FishMortality), With<Fish>>,
+    skeletons: Query<(), With<FishSkeleton>>,
+    mut spawn_events: EventWriter<FishSpawnEvent>,
+    mut birth_events: EventWriter<FishBirthEvent>,
+    temperature: Res<WaterTemperature>,
+) {
+    // fish breed more readily in warm water, but not when it is uncomfortably hot or cold.
+    let breeding_chance = temperature.metabolism().min(1.5) * (1. - temperature.stress())
+        / FISH_BREEDING_CHANCE as f32;
+
+    let mut census = Census {
+        fishes: fishes.iter().len(),
+        remains: skeletons.iter().len(),
+    };
+
+    let mut candidates: Vec<(Entity, Vec3)> = fishes
+        .iter()
+        .filter(|(_, _, mortality)| mortality.can_breed())
+        .map(|(entity, transform, _)| (entity, transform.translation))
+        .collect();
+
+    while let Some((entity, translation)) = candidates.pop() {
+        let Some(mate_index) = candidates
+            .iter()
+            .position(|(_, other)| translation.distance(*other) < FISH_BREEDING_DISTANCE)
+        else {
+            continue;
+        };
+
+        if !population.allows_breeding(&census)
+            || rng.next_u32() as f32 / u32::MAX as f32 >= breeding_chance
+        {
+            continue;
+        }
+
+        let (mate, mate_translation) = candidates.swap_remove(mate_index);
+
+        if let Ok([(_, _, mut mortality), (_, _, mut mate_mortality)]) =
+            fishes.get_many_mut([entity, mate])
+        {
+            mortality.breed();
+            mate_mortality.breed();
+        }
+
+        let birthplace = translation.midpoint(mate_translation);
+        spawn_events.write(FishSpawnEvent(birthplace));
+        birth_events.write(FishBirthEvent(birthplace));
+        census.fishes += 1;
+    }
+}
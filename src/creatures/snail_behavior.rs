@@ -2,7 +2,7 @@ use std::f32::consts::PI;
 
 use bevy::{ecs::query::QueryEntityError, prelude::*};
 
-use crate::general::AttemptDespawn;
+use crate::pellets::{Pellet, PelletEatenEvent};
 
 use super::behavior::{CreatureBehavior, CreatureOperations, CreatureRng};
 
@@ -37,10 +37,10 @@ impl CreatureOperations for SnailOperations<'_> {
         &mut self,
         time: &Time,
         rng: &mut CreatureRng,
-        pellet: Result<(Entity, &Transform), QueryEntityError>,
+        pellet: Result<(Entity, &Transform, &Pellet), QueryEntityError>,
         commands: &mut Commands,
     ) {
-        if let Ok((pellet_entity, pellet_transform)) = pellet {
+        if let Ok((pellet_entity, pellet_transform, _)) = pellet {
             if self.transform.translation.x < pellet_transform.translation.x {
                 self.face_right();
             } else {
@@ -50,7 +50,7 @@ impl CreatureOperations for SnailOperations<'_> {
             let (min, max) = Self::valid_area();
             self.transform.translation = self.transform.translation.move_towards(
                 pellet_transform.translation.clamp(min, max),
-                time.delta_secs() * Self::base_speed() * 2.,
+                time.delta_secs() * self.base_speed() * 2.,
             );
 
             if self
@@ -59,9 +59,36 @@ impl CreatureOperations for SnailOperations<'_> {
                 .distance(pellet_transform.translation)
                 < 0.1
             {
-                if let Ok(mut entity) = commands.get_entity(pellet_entity) {
-                    entity.insert(AttemptDespawn);
-                }
+                commands.send_event(PelletEatenEvent(pellet_entity));
+            }
+        } else {
+            self.start_seek_point(rng);
+        }
+    }
+
+    fn behavior_seek_carrion(
+        &mut self,
+        time: &Time,
+        rng: &mut CreatureRng,
+        carrion: Result<(Entity, &Transform), QueryEntityError>,
+        _commands: &mut Commands,
+    ) {
+        if let Ok((_, carrion_transform)) = carrion {
+            if self.transform.translation.x < carrion_transform.translation.x {
+                self.face_right();
+            } else {
+                self.face_left();
+            }
+
+            let (min, max) = Self::valid_area();
+            let target = carrion_transform.translation.clamp(min, max);
+            self.transform.translation = self
+                .transform
+                .translation
+                .move_towards(target, time.delta_secs() * self.base_speed() * 2.);
+
+            if self.transform.translation.distance(target) < 0.1 {
+                self.start_idle();
             }
         } else {
             self.start_seek_point(rng);
@@ -76,7 +103,12 @@ impl CreatureOperations for SnailOperations<'_> {
         *self.transform = self.transform.with_rotation(Quat::from_rotation_y(PI));
     }
 
-    fn rank_pellet(&mut self, pellet_transform: &Transform) -> f32 {
+    fn rank_pellet(
+        &mut self,
+        _pellet_id: Entity,
+        pellet_transform: &Transform,
+        _pellet: &Pellet,
+    ) -> f32 {
         self.transform()
             .translation
             .xz()
@@ -87,7 +119,18 @@ impl CreatureOperations for SnailOperations<'_> {
         true
     }
 
-    fn base_speed() -> f32 {
+    fn rank_carrion(&mut self, carrion_transform: &Transform) -> f32 {
+        self.transform()
+            .translation
+            .xz()
+            .distance(carrion_transform.translation.xz())
+    }
+
+    fn check_carrion(&mut self, rank: f32) -> bool {
+        rank < 1.5
+    }
+
+    fn base_speed(&self) -> f32 {
         0.05
     }
 
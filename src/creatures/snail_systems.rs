@@ -6,18 +6,19 @@ use crate::pellets::Pellet;
 
 use super::{
     behavior::{CreatureBehavior, CreatureOperations, CreatureRng},
+    lifecycle::FishSkeleton,
     snail_behavior::SnailOperations,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(Startup, setup_snails_system)
         .add_systems(
-            Update,
+            FixedUpdate,
             (
                 spawn_snails_system,
                 snails_behavior_system,
                 snails_behavior_change_system,
-                snails_pellet_detection_system.run_if(on_timer(Duration::from_secs_f32(0.5))),
+                snails_food_detection_system.run_if(on_timer(Duration::from_secs_f32(0.5))),
             ),
         )
         .add_event::<SnailSpawnEvent>();
@@ -65,7 +66,8 @@ fn snails_behavior_system(
     mut commands: Commands,
     time: Res<Time>,
     mut snails: Query<(&mut Transform, &mut CreatureBehavior), With<Snail>>,
-    pellets: Query<(Entity, &mut Transform), (With<Pellet>, Without<CreatureBehavior>)>,
+    pellets: Query<(Entity, &Transform, &Pellet), Without<CreatureBehavior>>,
+    carrion: Query<(Entity, &Transform), (With<FishSkeleton>, Without<CreatureBehavior>)>,
     mut rng: ResMut<CreatureRng>,
 ) {
     for (mut transform, mut behavior) in snails.iter_mut() {
@@ -74,6 +76,7 @@ fn snails_behavior_system(
             &mut rng,
             &time,
             &pellets,
+            &carrion,
         );
     }
 }
@@ -88,11 +91,14 @@ fn snails_behavior_change_system(
     }
 }
 
-fn snails_pellet_detection_system(
+fn snails_food_detection_system(
     mut snails: Query<(&mut Transform, &mut CreatureBehavior), With<Snail>>,
-    pellets: Query<(Entity, &Transform), (With<Pellet>, Without<CreatureBehavior>)>,
+    pellets: Query<(Entity, &Transform, &Pellet), Without<CreatureBehavior>>,
+    carrion: Query<(Entity, &Transform), (With<FishSkeleton>, Without<CreatureBehavior>)>,
 ) {
     for (mut transform, mut behavior) in snails.iter_mut() {
-        SnailOperations::new(&mut transform, &mut behavior).detect_pellet(&pellets);
+        let mut snail = SnailOperations::new(&mut transform, &mut behavior);
+        snail.detect_carrion(&carrion);
+        snail.detect_pellet(&pellets);
     }
 }
@@ -1,19 +1,99 @@
-use bevy::prelude::*;
+use std::f32::consts::PI;
 
-use super::behavior::{CreatureBehavior, CreatureBehaviorVariant, CreatureOperations, CreatureRng};
+use bevy::{ecs::query::QueryEntityError, prelude::*};
+use rand::RngCore;
+
+use crate::pellets::{Pellet, PelletEatenEvent};
+
+use super::{
+    behavior::{CreatureBehavior, CreatureBehaviorVariant, CreatureOperations, CreatureRng},
+    lifecycle::StarfishMortality,
+};
+
+pub(super) const STARFISH_MIN: usize = 2;
+pub(super) const STARFISH_REPOPULATE_INTERVAL_SECONDS: u64 = 90;
+pub(super) const STARFISH_AGING_INTERVAL_SECONDS: f32 = 20.;
+pub(super) const STARFISH_SATIATION_MAX: u32 = 32;
+pub(super) const STARFISH_AVERAGE_LONGEVITY: u32 = 192;
+pub(super) const STARFISH_SIZE_MAX: f32 = 0.22;
+pub(super) const STARFISH_FRAGMENT_SIZE: f32 = 0.08;
+pub(super) const STARFISH_GROWTH_RATE: f32 = 0.01;
+pub(super) const STARFISH_REGROWTH_RATE: f32 = 0.05;
+
+const STARFISH_GLASS_Z: f32 = -0.41;
+const STARFISH_GRAVEL_Y: f32 = -1.7;
+const STARFISH_SURFACE_EPSILON: f32 = 0.01;
 
 pub struct StarfishOperations<'a> {
     transform: &'a mut Transform,
     behavior: &'a mut CreatureBehavior,
+    mortality: &'a mut StarfishMortality,
+    spin: &'a mut f32,
 }
 
 impl<'a> StarfishOperations<'a> {
-    pub(super) fn new(transform: &'a mut Transform, behavior: &'a mut CreatureBehavior) -> Self {
+    pub(super) fn new(
+        transform: &'a mut Transform,
+        behavior: &'a mut CreatureBehavior,
+        mortality: &'a mut StarfishMortality,
+        spin: &'a mut f32,
+    ) -> Self {
         Self {
             transform,
             behavior,
+            mortality,
+            spin,
         }
     }
+
+    pub(super) fn animate(&mut self, time: &Time) {
+        let crawling = matches!(
+            self.behavior.variant,
+            CreatureBehaviorVariant::SeekPoint(_)
+                | CreatureBehaviorVariant::SeekPellet(_)
+                | CreatureBehaviorVariant::SeekCarrion(_)
+        );
+
+        let surface = if on_gravel(self.transform.translation) {
+            Quat::from_rotation_x(-PI / 2.)
+        } else {
+            Quat::IDENTITY
+        };
+        let target = surface * Quat::from_rotation_z(*self.spin);
+        self.transform.rotation = self
+            .transform
+            .rotation
+            .slerp(target, (time.delta_secs() * 2.).min(1.));
+
+        let phase =
+            time.elapsed_secs() * if crawling { 3. } else { 0.5 } + self.mortality.size * 40.;
+        let amplitude = if crawling { 0.08 } else { 0.02 };
+        let size = self.mortality.size * (0.6 + 0.4 * self.mortality.regrowth);
+        self.transform.scale = Vec3::new(
+            size * (1. + amplitude * phase.sin()),
+            size * (1. + amplitude * (phase + PI / 2.).sin()),
+            size,
+        );
+    }
+
+    fn crawl_towards(&mut self, target: Vec3, speed: f32) {
+        let position = self.transform.translation;
+
+        // crossing between the back glass and the gravel goes by way of the corner between them.
+        let waypoint =
+            if on_glass(position) != on_glass(target) && on_gravel(position) != on_gravel(target) {
+                Vec3::new(position.x, STARFISH_GRAVEL_Y, STARFISH_GLASS_Z)
+            } else {
+                target
+            };
+
+        self.transform.translation = position.move_towards(waypoint, speed);
+    }
+
+    fn eat(&mut self, nutrition: u32) {
+        self.mortality.satiation =
+            (self.mortality.satiation + nutrition).min(STARFISH_SATIATION_MAX);
+    }
 }
 
 impl CreatureOperations for StarfishOperations<'_> {
@@ -23,6 +103,8 @@ impl CreatureOperations for StarfishOperations<'_> {
         if self.behavior().timer.just_finished() {
             match self.behavior().variant {
                 CreatureBehaviorVariant::Idle => self.start_seek_point(rng),
+                CreatureBehaviorVariant::SeekPellet(_)
+                | CreatureBehaviorVariant::SeekCarrion(_) => {}
                 _ => self.start_idle(),
             }
         }
@@ -33,16 +115,148 @@ impl CreatureOperations for StarfishOperations<'_> {
     }
 
     fn behavior_idle(&mut self, time: &Time) {
-        self.transform()
-            .rotate_z((time.elapsed_secs() / 10.).sin() * Self::base_speed() * -0.1);
+        *self.spin += (time.elapsed_secs() / 10.).sin() * self.base_speed() * -0.03;
+    }
+
+    fn behavior_seek_point(&mut self, time: &Time, target: Vec3) {
+        self.crawl_towards(target, time.delta_secs() * self.base_speed());
+
+        if self.transform.translation.distance(target) < 0.05 {
+            self.start_idle();
+        }
     }
 
-    fn base_speed() -> f32 {
-        0.01
+    fn behavior_seek_pellet(
+        &mut self,
+        time: &Time,
+        _rng: &mut CreatureRng,
+        pellet: Result<(Entity, &Transform, &Pellet), QueryEntityError>,
+        commands: &mut Commands,
+    ) {
+        let Ok((pellet_entity, pellet_transform, _)) = pellet else {
+            self.start_idle();
+            return;
+        };
+
+        if self.mortality.satiation >= STARFISH_SATIATION_MAX {
+            self.start_idle();
+            return;
+        }
+
+        self.crawl_towards(
+            pellet_transform.translation,
+            time.delta_secs() * self.base_speed() * 2.,
+        );
+
+        if self
+            .transform
+            .translation
+            .distance(pellet_transform.translation)
+            < 0.1
+        {
+            self.eat(4);
+            commands.send_event(PelletEatenEvent(pellet_entity));
+            self.start_idle();
+        }
+    }
+
+    fn behavior_seek_carrion(
+        &mut self,
+        time: &Time,
+        _rng: &mut CreatureRng,
+        carrion: Result<(Entity, &Transform), QueryEntityError>,
+        _commands: &mut Commands,
+    ) {
+        let Ok((_, carrion_transform)) = carrion else {
+            self.start_idle();
+            return;
+        };
+
+        if self.mortality.satiation >= STARFISH_SATIATION_MAX {
+            self.start_idle();
+            return;
+        }
+
+        let target = carrion_transform
+            .translation
+            .with_y(STARFISH_GRAVEL_Y)
+            .clamp(Self::valid_area().0, Self::valid_area().1);
+        self.crawl_towards(target, time.delta_secs() * self.base_speed() * 2.);
+
+        // feeding itself happens while the starfish lingers next to the decomposing remains.
+        if self.transform.translation.distance(target) < 0.1 {
+            self.start_idle();
+        }
+    }
+
+    fn clamp(&mut self) {
+        let (min, max) = Self::valid_area();
+        let position = self.transform.translation.clamp(min, max);
+
+        // starfish cling to whichever surface is closest, either the back glass or the gravel.
+        self.transform.translation =
+            if position.y - STARFISH_GRAVEL_Y < position.z - STARFISH_GLASS_Z {
+                position.with_y(STARFISH_GRAVEL_Y)
+            } else {
+                position.with_z(STARFISH_GLASS_Z)
+            };
+    }
+
+    fn rank_pellet(
+        &mut self,
+        _pellet_id: Entity,
+        pellet_transform: &Transform,
+        _pellet: &Pellet,
+    ) -> f32 {
+        if pellet_transform.translation.y > STARFISH_GRAVEL_Y + STARFISH_SURFACE_EPSILON {
+            return f32::MAX;
+        }
+
+        self.transform
+            .translation
+            .distance(pellet_transform.translation)
+    }
+
+    fn check_pellet(&mut self, rank: f32) -> bool {
+        rank < 1.2 && self.mortality.satiation < STARFISH_SATIATION_MAX * 3 / 4
+    }
+
+    fn rank_carrion(&mut self, carrion_transform: &Transform) -> f32 {
+        if carrion_transform.translation.y > STARFISH_GRAVEL_Y + 0.2 {
+            return f32::MAX;
+        }
+
+        self.transform
+            .translation
+            .distance(carrion_transform.translation)
+    }
+
+    fn check_carrion(&mut self, rank: f32) -> bool {
+        rank < 1.5 && self.mortality.satiation < STARFISH_SATIATION_MAX / 2
+    }
+
+    fn base_speed(&self) -> f32 {
+        0.03
     }
 
     fn valid_area() -> (Vec3, Vec3) {
-        (Vec3::new(-1.4, -1.4, -0.41), Vec3::new(1.4, 1.4, -0.41))
+        (
+            Vec3::new(-1.4, STARFISH_GRAVEL_Y, STARFISH_GLASS_Z),
+            Vec3::new(1.4, 1.4, 0.3),
+        )
+    }
+
+    fn valid_random_point(rng: &mut CreatureRng) -> Vec3 {
+        let (min, max) = Self::valid_area();
+        let buffer = Self::valid_point_buffer();
+        let x = min.x + buffer.x + (max.x - min.x - 2. * buffer.x) * unit(rng);
+
+        if rng.next_u32() % 3 == 0 {
+            Vec3::new(x, STARFISH_GRAVEL_Y, min.z + (max.z - min.z) * unit(rng))
+        } else {
+            let y = min.y + buffer.y + (max.y - min.y - 2. * buffer.y) * unit(rng);
+            Vec3::new(x, y, STARFISH_GLASS_Z)
+        }
     }
 
     fn behavior(&mut self) -> &mut CreatureBehavior {
@@ -57,3 +271,15 @@ impl CreatureOperations for StarfishOperations<'_> {
         Vec3::new(0.1, 0.1, 0.)
     }
 }
+
+fn on_glass(position: Vec3) -> bool {
+    position.z <= STARFISH_GLASS_Z + STARFISH_SURFACE_EPSILON
+}
+
+fn on_gravel(position: Vec3) -> bool {
+    position.y <= STARFISH_GRAVEL_Y + STARFISH_SURFACE_EPSILON
+}
+
+fn unit(rng: &mut CreatureRng) -> f32 {
+    rng.next_u32() as f32 / u32::MAX as f32
+}
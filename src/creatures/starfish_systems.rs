@@ -1,36 +1,51 @@
 use std::time::Duration;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, time::common_conditions::on_timer};
 
 use crate::pellets::Pellet;
 
 use super::{
-    behavior::{CreatureBehavior, CreatureOperations, CreatureRng},
-    starfish_behavior::StarfishOperations,
+    behavior::{CreatureBehavior, CreatureBehaviorVariant, CreatureOperations, CreatureRng},
+    lifecycle::{FishSkeleton, StarfishMortality},
+    starfish_behavior::{
+        StarfishOperations, STARFISH_FRAGMENT_SIZE, STARFISH_REPOPULATE_INTERVAL_SECONDS,
+    },
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(Startup, setup_starfishes_system)
         .add_systems(
-            Update,
+            FixedUpdate,
             (
+                populate_starfishes_system.run_if(on_timer(Duration::from_secs(
+                    STARFISH_REPOPULATE_INTERVAL_SECONDS,
+                ))),
                 spawn_starfishes_system,
                 starfishes_behavior_system,
                 starfishes_behavior_change_system,
+                starfishes_food_detection_system.run_if(on_timer(Duration::from_secs_f32(1.))),
             ),
         )
-        .add_event::<StarfishSpawnEvent>();
+        .add_event::<StarfishSpawnEvent>()
+        .add_event::<StarfishAttackEvent>();
 }
 
 #[derive(Component)]
 pub struct Starfish;
 
+#[derive(Component, Deref, DerefMut)]
+pub struct StarfishSpin(f32);
+
 #[derive(Resource, Deref)]
 pub struct StarfishScene(Handle<Scene>);
 
 #[derive(Event)]
 pub struct StarfishSpawnEvent(pub Vec3, pub f32);
 
+/// Sent when a fish nips at a starfish, with the fraction of its arms lost.
+#[derive(Event)]
+pub struct StarfishAttackEvent(pub Entity, pub f32);
+
 fn setup_starfishes_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -41,12 +56,27 @@ fn setup_starfishes_system(
     commands.insert_resource(StarfishScene(starfish));
 
     spawn_events.write(StarfishSpawnEvent(Vec3::new(-0.3, -1.3, -0.4), 0.2));
+    spawn_events.write(StarfishSpawnEvent(Vec3::new(0.9, -1.7, 0.1), 0.12));
+}
+
+fn populate_starfishes_system(
+    mut rng: ResMut<CreatureRng>,
+    starfishes: Query<Entity, With<Starfish>>,
+    mut spawn_events: EventWriter<StarfishSpawnEvent>,
+) {
+    if starfishes.is_empty() {
+        spawn_events.write(StarfishSpawnEvent(
+            StarfishOperations::valid_random_point(&mut rng),
+            STARFISH_FRAGMENT_SIZE,
+        ));
+    }
 }
 
 fn spawn_starfishes_system(
     mut commands: Commands,
     mut spawn_events: EventReader<StarfishSpawnEvent>,
     fish_scene: Res<StarfishScene>,
+    mut rng: ResMut<CreatureRng>,
 ) {
     for StarfishSpawnEvent(location, size) in spawn_events.read() {
         let transform = Transform::from_translation(*location)
@@ -55,33 +85,91 @@ fn spawn_starfishes_system(
         let mut behavior = CreatureBehavior::default();
         behavior.timer.set_duration(Duration::from_secs(12));
 
-        commands.spawn((Starfish, behavior, SceneRoot(fish_scene.clone()), transform));
+        commands.spawn((
+            Starfish,
+            StarfishSpin(1.),
+            StarfishMortality::new(&mut rng, *size),
+            behavior,
+            SceneRoot(fish_scene.clone()),
+            transform,
+        ));
     }
 }
 
 fn starfishes_behavior_system(
     mut commands: Commands,
     time: Res<Time>,
-    mut starfishes: Query<(&mut Transform, &mut CreatureBehavior), With<Starfish>>,
-    pellets: Query<(Entity, &mut Transform), (With<Pellet>, Without<CreatureBehavior>)>,
+    mut starfishes: Query<
+        (
+            &mut Transform,
+            &mut CreatureBehavior,
+            &mut StarfishMortality,
+            &mut StarfishSpin,
+        ),
+        With<Starfish>,
+    >,
+    pellets: Query<(Entity, &Transform, &Pellet), Without<CreatureBehavior>>,
+    carrion: Query<(Entity, &Transform), (With<FishSkeleton>, Without<CreatureBehavior>)>,
     mut rng: ResMut<CreatureRng>,
 ) {
-    for (mut transform, mut behavior) in starfishes.iter_mut() {
-        StarfishOperations::new(&mut transform, &mut behavior).do_behavior(
-            &mut commands,
-            &mut rng,
-            &time,
-            &pellets,
-        );
+    for (mut transform, mut behavior, mut mortality, mut spin) in starfishes.iter_mut() {
+        let mut starfish =
+            StarfishOperations::new(&mut transform, &mut behavior, &mut mortality, &mut spin);
+        starfish.do_behavior(&mut commands, &mut rng, &time, &pellets, &carrion);
+        starfish.animate(&time);
     }
 }
 
 fn starfishes_behavior_change_system(
     time: Res<Time>,
-    mut starfishes: Query<(&mut Transform, &mut CreatureBehavior), With<Starfish>>,
+    mut starfishes: Query<
+        (
+            &mut Transform,
+            &mut CreatureBehavior,
+            &mut StarfishMortality,
+            &mut StarfishSpin,
+        ),
+        With<Starfish>,
+    >,
     mut rng: ResMut<CreatureRng>,
 ) {
-    for (mut transform, mut behavior) in starfishes.iter_mut() {
-        StarfishOperations::new(&mut transform, &mut behavior).decide_behavior(&time, &mut rng);
+    for (mut transform, mut behavior, mut mortality, mut spin) in starfishes.iter_mut() {
+        StarfishOperations::new(&mut transform, &mut behavior, &mut mortality, &mut spin)
+            .decide_behavior(&time, &mut rng);
+    }
+}
+
+fn starfishes_food_detection_system(
+    mut starfishes: Query<
+        (
+            &mut Transform,
+            &mut CreatureBehavior,
+            &mut StarfishMortality,
+            &mut StarfishSpin,
+        ),
+        With<Starfish>,
+    >,
+    pellets: Query<(Entity, &Transform, &Pellet), Without<CreatureBehavior>>,
+    carrion: Query<(Entity, &Transform), (With<FishSkeleton>, Without<CreatureBehavior>)>,
+) {
+    for (mut transform, mut behavior, mut mortality, mut spin) in starfishes.iter_mut() {
+        // a starfish already crawling towards food sees it through.
+        if is_seeking(&behavior) {
+            continue;
+        }
+
+        let mut starfish =
+            StarfishOperations::new(&mut transform, &mut behavior, &mut mortality, &mut spin);
+        starfish.detect_carrion(&carrion);
+        if !is_seeking(starfish.behavior()) {
+            starfish.detect_pellet(&pellets);
+        }
     }
 }
+
+fn is_seeking(behavior: &CreatureBehavior) -> bool {
+    matches!(
+        behavior.variant,
+        CreatureBehaviorVariant::SeekPellet(_) | CreatureBehaviorVariant::SeekCarrion(_)
+    )
+}
@@ -3,26 +3,128 @@ use bevy::{diagnostic::DiagnosticsStore, prelude::*};
 use bevy_ratatui::RatatuiContext;
 use bevy_ratatui_camera::RatatuiCameraWidget;
 use ratatui::style::Stylize;
-use ratatui::widgets::Widget;
+use ratatui::widgets::{Block, Clear, Gauge, Paragraph, Sparkline, Widget};
 use ratatui::{
-    layout::{Alignment, Rect},
-    text::Text,
+    layout::{Alignment, Constraint, Layout, Rect},
+    text::{Line, Text},
+    Frame,
 };
 
-use crate::Flags;
+use crate::{
+    capture::{Capture, HeadlessTerminal},
+    colors::ColorOutput,
+    creatures::{
+        illness::{Illness, Medication},
+        lifecycle::{DeathRecord, FishFeeding, FishMortality},
+        personality::{FishPersonality, Inspector},
+        population::Population,
+    },
+    focus::{FocusPhase, FocusSession},
+    graphics::{draw_image, ImageOutput},
+    input::KeyboardCursor,
+    keymap::Keymap,
+    simulation::SimulationSpeed,
+    stats::Stats,
+    temperature::{
+        Heater, WaterTemperature, COMFORT_MAX, COMFORT_MIN, THERMOMETER_MAX, THERMOMETER_MIN,
+    },
+    toast::Toasts,
+    Flags,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(PostUpdate, draw_scene_system);
 }
 
 fn draw_scene_system(
-    mut ratatui: ResMut<RatatuiContext>,
+    mut ratatui: Option<ResMut<RatatuiContext>>,
+    mut headless: Option<ResMut<HeadlessTerminal>>,
+    time: Res<Time<Real>>,
+    mut capture: ResMut<Capture>,
     mut camera: Single<&mut RatatuiCameraWidget>,
     flags: Res<Flags>,
     diagnostics: Res<DiagnosticsStore>,
+    // grouped, since a system can take no more than sixteen parameters.
+    (population, inspector, inspected, death_record): (
+        Res<Population>,
+        Res<Inspector>,
+        Query<(&FishPersonality, &FishMortality, &FishFeeding, Has<Illness>)>,
+        Res<DeathRecord>,
+    ),
+    (temperature, heater, medication): (Res<WaterTemperature>, Single<&Heater>, Res<Medication>),
+    stats: Res<Stats>,
+    (toasts, keymap, cursor): (Res<Toasts>, Res<Keymap>, Res<KeyboardCursor>),
+    focus: Res<FocusSession>,
+    colors: Res<ColorOutput>,
+    speed: Res<SimulationSpeed>,
+    image_output: Res<ImageOutput>,
 ) -> Result {
-    ratatui.draw(|frame| {
-        camera.render(frame.area(), frame.buffer_mut());
+    let recording = capture.shows_badge();
+
+    let image = image_output.drawing();
+
+    let draw = |frame: &mut Frame| {
+        // with an image protocol the tank goes out as pixels afterwards, under or over the cells.
+        if image.is_none() {
+            camera.render(frame.area(), frame.buffer_mut());
+        }
+
+        render_thermometer(frame, **temperature, heater.setpoint);
+
+        if flags.stats {
+            render_stats(frame, &stats);
+        }
+
+        render_focus(frame, &focus);
+
+        let mut badge_edge = (frame.area().width / 2
+            + frame.area().width.min(frame.area().height * 2) / 2)
+            .saturating_sub(2);
+
+        if recording {
+            let position = next_badge(frame.area(), &mut badge_edge, 5);
+            let rec = Text::raw(" rec ")
+                .alignment(Alignment::Center)
+                .bg(ratatui::style::Color::Red)
+                .fg(ratatui::style::Color::White);
+
+            frame.render_widget(rec, position);
+        }
+
+        if speed.multiplier() != 1. {
+            let label = if speed.paused() {
+                " paused ".to_string()
+            } else {
+                format!(" {}x ", speed.multiplier())
+            };
+            let position = next_badge(frame.area(), &mut badge_edge, label.len() as u16);
+            let speed = Text::raw(label)
+                .alignment(Alignment::Center)
+                .bg(ratatui::style::Color::White)
+                .fg(ratatui::style::Color::Black);
+
+            frame.render_widget(speed, position);
+        }
+
+        if flags.muted {
+            let position = next_badge(frame.area(), &mut badge_edge, 7);
+            let muted = Text::raw(" muted ")
+                .alignment(Alignment::Center)
+                .bg(ratatui::style::Color::White)
+                .fg(ratatui::style::Color::Black);
+
+            frame.render_widget(muted, position);
+        }
+
+        if medication.is_active() {
+            let position = next_badge(frame.area(), &mut badge_edge, 11);
+            let medicated = Text::raw(" medicated ")
+                .alignment(Alignment::Center)
+                .bg(ratatui::style::Color::White)
+                .fg(ratatui::style::Color::Black);
+
+            frame.render_widget(medicated, position);
+        }
 
         if flags.debug {
             if let Some(value) = diagnostics
@@ -30,38 +132,397 @@ fn draw_scene_system(
                 .and_then(|fps| fps.smoothed())
             {
                 let _msg = &flags.msg;
+                let position = next_badge(frame.area(), &mut badge_edge, 9);
+                let fps = Text::raw(format!(" fps: {value:.0} "))
+                    .alignment(Alignment::Center)
+                    .bg(ratatui::style::Color::Black)
+                    .fg(ratatui::style::Color::White);
+
+                frame.render_widget(fps, position);
+            }
+
+            let lines = [
+                format!(
+                    " population: {} ({}) ",
+                    population.kind().name(),
+                    population.parameters()
+                ),
+                format!(
+                    " deaths: {} old age, {} starved, {} illness ",
+                    death_record.old_age, death_record.starvation, death_record.illness
+                ),
+            ];
+
+            for (row, line) in lines.into_iter().enumerate() {
                 let position = Rect::new(
                     (frame.area().width / 2 + frame.area().width.min(frame.area().height * 2) / 2)
-                        .saturating_sub(11 + if flags.muted { 8 } else { 0 }),
-                    1 + (frame.area().height * 2).saturating_sub(frame.area().width) / 4,
-                    9,
+                        .saturating_sub(line.len() as u16 + 2),
+                    2 + row as u16
+                        + (frame.area().height * 2).saturating_sub(frame.area().width) / 4,
+                    line.len() as u16,
                     1,
-                );
-                let fps = Text::raw(format!(" fps: {value:.0} "))
+                )
+                .intersection(frame.area());
+                let line = Text::raw(line)
                     .alignment(Alignment::Center)
                     .bg(ratatui::style::Color::Black)
                     .fg(ratatui::style::Color::White);
 
-                frame.render_widget(fps, position);
+                frame.render_widget(line, position);
+            }
+
+            if let Some((personality, mortality, feeding, sick)) =
+                inspector.and_then(|entity| inspected.get(entity).ok())
+            {
+                let lines: Vec<Line> = [
+                    format!("age       {:>3}/{}", mortality.age(), mortality.longevity()),
+                    format!("satiation {:>3}", mortality.satiation()),
+                    format!("health    {}", if sick { "sick" } else { "well" }),
+                    format!("boldness  {:.2}", personality.boldness),
+                    format!("curiosity {:.2}", personality.curiosity),
+                    format!("laziness  {:.2}", personality.laziness),
+                    format!("greed     {:.2}", personality.greed),
+                    format!("bites     {:>3}", feeding.bites),
+                    format!("pellets   {:>3}", feeding.pellets_finished),
+                    format!(
+                        "nudged    {:>3}/{}",
+                        feeding.nudges_given, feeding.nudged_away
+                    ),
+                ]
+                .into_iter()
+                .map(Line::from)
+                .collect();
+
+                let position = Rect::new(
+                    (frame.area().width / 2)
+                        .saturating_sub(frame.area().width.min(frame.area().height * 2) / 2)
+                        + 2,
+                    1 + (frame.area().height * 2).saturating_sub(frame.area().width) / 4,
+                    19,
+                    lines.len() as u16 + 2,
+                )
+                .intersection(frame.area());
+                let inspector = Paragraph::new(lines)
+                    .block(Block::bordered().title(" fish "))
+                    .bg(ratatui::style::Color::Black)
+                    .fg(ratatui::style::Color::White);
+
+                frame.render_widget(Clear, position);
+                frame.render_widget(inspector, position);
             }
         }
 
-        if flags.muted {
-            let position = Rect::new(
-                (frame.area().width / 2 + frame.area().width.min(frame.area().height * 2) / 2)
-                    .saturating_sub(9),
-                1 + (frame.area().height * 2).saturating_sub(frame.area().width) / 4,
-                7,
-                1,
-            );
-            let fps = Text::raw(" muted ")
-                .alignment(Alignment::Center)
-                .bg(ratatui::style::Color::White)
-                .fg(ratatui::style::Color::Black);
+        if let Some(position) = cursor.visible_position() {
+            render_cursor(frame, position);
+        }
 
-            frame.render_widget(fps, position);
+        if let Some((text, opacity)) = toasts.current() {
+            render_toast(frame, text, opacity);
         }
-    })?;
+
+        if flags.help {
+            render_help(frame, &keymap);
+        }
+
+        colors.quantize(frame.buffer_mut());
+    };
+
+    // recordings made without a terminal draw into a pretend one instead.
+    let completed = if let Some(ratatui) = ratatui.as_deref_mut() {
+        ratatui.draw(draw)?
+    } else if let Some(headless) = headless.as_deref_mut() {
+        headless.draw(draw)?
+    } else {
+        return Ok(());
+    };
+
+    if capture.wants_frame() {
+        capture.frame(time.elapsed(), completed.buffer, &camera.camera_image);
+    }
+
+    let cells = image.map(|_| completed.buffer.clone());
+    if let (Some(protocol), Some(cells), Some(ratatui)) = (image, cells, ratatui.as_deref_mut()) {
+        draw_image(ratatui, protocol, &camera.camera_image, &cells)?;
+    }
 
     Ok(())
 }
+
+/// Places a badge of the given width along the top edge of the tank, right to left.
+fn next_badge(area: Rect, edge: &mut u16, width: u16) -> Rect {
+    let x = edge.saturating_sub(width);
+    *edge = x.saturating_sub(1);
+
+    Rect::new(
+        x,
+        1 + (area.height * 2).saturating_sub(area.width) / 4,
+        width,
+        1,
+    )
+}
+
+/// Draws a thermometer along the bottom left of the tank, marking the heater setpoint.
+fn render_thermometer(frame: &mut Frame, temperature: f32, setpoint: f32) {
+    const BAR_HEIGHT: u16 = 8;
+
+    let area = frame.area();
+    let side = area.width.min(area.height * 2);
+    let bottom = (area.height * 2).saturating_sub(area.width) / 4 + side / 2;
+
+    let level = |value: f32| {
+        ((value - THERMOMETER_MIN) / (THERMOMETER_MAX - THERMOMETER_MIN) * BAR_HEIGHT as f32)
+            .round()
+            .clamp(0., BAR_HEIGHT as f32) as u16
+    };
+    let (filled, marked) = (level(temperature), level(setpoint));
+
+    let color = if temperature < COMFORT_MIN {
+        ratatui::style::Color::Blue
+    } else if temperature > COMFORT_MAX {
+        ratatui::style::Color::Red
+    } else {
+        ratatui::style::Color::Green
+    };
+
+    let mut lines = vec![Line::from(format!("{temperature:>3.0}°"))];
+    lines.extend((1..=BAR_HEIGHT).rev().map(|row| {
+        let bar = if row <= filled { " █" } else { " │" };
+        let marker = if row == marked { "◂" } else { " " };
+        Line::from(vec![bar.fg(color), marker.into()])
+    }));
+    lines.push(Line::from(" ●".fg(color)));
+
+    let position = Rect::new(
+        (area.width / 2).saturating_sub(side / 2) + 2,
+        bottom.saturating_sub(lines.len() as u16 + 1),
+        4,
+        lines.len() as u16,
+    )
+    .intersection(area);
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .bg(ratatui::style::Color::Black)
+            .fg(ratatui::style::Color::White),
+        position,
+    );
+}
+
+/// Draws the focus timer as a gauge along the top left of the tank, while one is running.
+fn render_focus(frame: &mut Frame, focus: &FocusSession) {
+    let (label, color) = match focus.phase() {
+        FocusPhase::Idle => return,
+        FocusPhase::Focus => ("focus", ratatui::style::Color::Red),
+        FocusPhase::Break => ("break", ratatui::style::Color::Green),
+    };
+
+    let area = frame.area();
+    let side = area.width.min(area.height * 2);
+    let remaining = focus.remaining().as_secs();
+
+    let position = Rect::new(
+        (area.width / 2).saturating_sub(side / 2) + 2,
+        1 + (area.height * 2).saturating_sub(area.width) / 4,
+        side.saturating_sub(4).min(24),
+        1,
+    )
+    .intersection(area);
+
+    let gauge = Gauge::default()
+        .ratio(focus.progress() as f64)
+        .label(format!(
+            "{label} {:02}:{:02}",
+            remaining / 60,
+            remaining % 60
+        ))
+        .gauge_style(
+            ratatui::style::Style::new()
+                .fg(color)
+                .bg(ratatui::style::Color::Black),
+        );
+
+    frame.render_widget(gauge, position);
+}
+
+/// Draws the statistics panel along the bottom right of the tank, with a sparkline per metric.
+fn render_stats(frame: &mut Frame, stats: &Stats) {
+    let area = frame.area();
+    let side = area.width.min(area.height * 2);
+    let bottom = (area.height * 2).saturating_sub(area.width) / 4 + side / 2;
+    let width = side.saturating_sub(10).min(44);
+    let height = 19;
+
+    let position = Rect::new(
+        (area.width / 2 + side / 2).saturating_sub(width + 2),
+        bottom.saturating_sub(height + 1),
+        width,
+        height,
+    )
+    .intersection(area);
+
+    let current = &stats.current;
+    let (births, deaths) = stats.hourly();
+    let summary: Vec<Line> = [
+        format!(
+            "fish {}  snails {}  starfish {}",
+            current.fishes, current.snails, current.starfish
+        ),
+        format!(
+            "age {:.1}  satiation {:.1}",
+            current.average_age, current.average_satiation
+        ),
+        format!("births {births}/h  deaths {deaths}/h"),
+        format!(
+            "pellets {} in, {} eaten, {} rotted",
+            current.pellets.dispensed, current.pellets.eaten, current.pellets.rotted
+        ),
+    ]
+    .into_iter()
+    .map(Line::from)
+    .collect();
+
+    let block = Block::bordered()
+        .title(" stats ")
+        .bg(ratatui::style::Color::Black)
+        .fg(ratatui::style::Color::White);
+    let inner = block.inner(position);
+
+    frame.render_widget(Clear, position);
+    frame.render_widget(block, position);
+
+    let [summary_area, charts_area] = Layout::vertical([
+        Constraint::Length(summary.len() as u16 + 1),
+        Constraint::Fill(1),
+    ])
+    .areas(inner);
+
+    frame.render_widget(Paragraph::new(summary), summary_area);
+
+    let recent = |data: Vec<u64>| data[data.len().saturating_sub(inner.width as usize)..].to_vec();
+    let charts = [
+        (
+            "population",
+            recent(
+                stats
+                    .history
+                    .iter()
+                    .map(|sample| sample.fishes as u64)
+                    .collect(),
+            ),
+        ),
+        (
+            "satiation",
+            recent(
+                stats
+                    .history
+                    .iter()
+                    .map(|sample| sample.average_satiation.round() as u64)
+                    .collect(),
+            ),
+        ),
+        ("births", recent(stats.deltas(|sample| sample.births))),
+        ("deaths", recent(stats.deltas(|sample| sample.deaths))),
+    ];
+
+    let rows = Layout::vertical([Constraint::Length(3); 4]).split(charts_area);
+    for ((label, data), row) in charts.iter().zip(rows.iter()) {
+        let [label_area, sparkline_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Length(2)]).areas(*row);
+
+        frame.render_widget(Line::from(*label).dim(), label_area);
+        frame.render_widget(
+            Sparkline::default()
+                .data(data)
+                .style(ratatui::style::Color::Cyan),
+            sparkline_area,
+        );
+    }
+}
+
+/// Draws a centered list of every control.
+fn render_help(frame: &mut Frame, keymap: &Keymap) {
+    let entries = keymap.help_entries();
+    let key_width = entries
+        .iter()
+        .map(|(keys, _)| keys.len())
+        .max()
+        .unwrap_or(0);
+
+    let lines: Vec<Line> = entries
+        .iter()
+        .map(|(keys, description)| {
+            Line::from(vec![
+                format!(" {keys:>key_width$}  ").bold(),
+                format!("{description} ").into(),
+            ])
+        })
+        .collect();
+
+    let area = frame.area();
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 2;
+    let height = lines.len() as u16 + 2;
+    let position = Rect::new(
+        (area.width / 2).saturating_sub(width / 2),
+        (area.height / 2).saturating_sub(height / 2),
+        width,
+        height,
+    )
+    .intersection(area);
+
+    let help = Paragraph::new(lines)
+        .block(Block::bordered().title(" help "))
+        .bg(ratatui::style::Color::Black)
+        .fg(ratatui::style::Color::White);
+
+    frame.render_widget(Clear, position);
+    frame.render_widget(help, position);
+}
+
+/// Draws a message along the bottom of the tank, dimming it as it fades out.
+fn render_toast(frame: &mut Frame, text: &str, opacity: f32) {
+    let area = frame.area();
+    let side = area.width.min(area.height * 2);
+    let bottom = (area.height * 2).saturating_sub(area.width) / 4 + side / 2;
+
+    let text = format!(" {text} ");
+    let width = text.chars().count() as u16;
+    let position = Rect::new(
+        (area.width / 2).saturating_sub(width / 2),
+        bottom.saturating_sub(3),
+        width,
+        1,
+    )
+    .intersection(area);
+
+    let brightness = (48. + 207. * opacity) as u8;
+    let toast = Text::raw(text)
+        .alignment(Alignment::Center)
+        .bg(ratatui::style::Color::Black)
+        .fg(ratatui::style::Color::Rgb(
+            brightness, brightness, brightness,
+        ));
+
+    frame.render_widget(toast, position);
+}
+
+/// Draws the keyboard feeding cursor as a small crosshair.
+fn render_cursor(frame: &mut Frame, position: IVec2) {
+    let crosshair = [
+        (IVec2::ZERO, '┼'),
+        (IVec2::NEG_X, '─'),
+        (IVec2::X, '─'),
+        (IVec2::NEG_Y, '│'),
+        (IVec2::Y, '│'),
+    ];
+
+    for (offset, symbol) in crosshair {
+        let cell = position + offset;
+        if cell.x < 0 || cell.y < 0 {
+            continue;
+        }
+
+        if let Some(cell) = frame.buffer_mut().cell_mut((cell.x as u16, cell.y as u16)) {
+            cell.set_char(symbol).set_fg(ratatui::style::Color::Yellow);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    audio::{Sfx, SfxEvent},
+    config::config_section,
+    toast::ToastEvent,
+    water::WATER_SURFACE_HEIGHT,
+    Flags,
+};
+
+const FILTER_POSITION: Vec3 = Vec3::new(1.6, 1.45, -0.35);
+const FILTER_INTAKE_BOTTOM: f32 = -1.4;
+/// Fastest the water moves with the filter at full strength, in world units per second.
+const CURRENT_SPEED: f32 = 0.12;
+/// Extra push right where the water pours out of the filter.
+const OUTFLOW_SPEED: f32 = 0.15;
+const OUTFLOW_SPREAD: f32 = 0.15;
+/// How quickly the current picks up or dies down, as a fraction of full flow per second.
+const FLOW_RAMP_RATE: f32 = 0.4;
+const TANK_LEFT: f32 = -1.8;
+const TANK_RIGHT: f32 = 1.8;
+const TANK_BOTTOM: f32 = -1.75;
+const ARROW_SPACING: f32 = 0.4;
+const ARROW_SCALE: f32 = 2.5;
+
+pub(super) fn plugin(app: &mut App) {
+    let config: FilterConfig = config_section(app, "filter");
+
+    app.add_systems(Startup, setup_filter_system)
+        .add_systems(
+            Update,
+            (
+                toggle_filter_system,
+                draw_current_system.run_if(|flags: Res<Flags>| flags.debug),
+            ),
+        )
+        .add_systems(FixedUpdate, ramp_flow_system)
+        .insert_resource(Current {
+            on: config.on,
+            strength: config.strength.max(0.),
+            flow: if config.on { 1. } else { 0. },
+        })
+        .add_event::<FilterEvent>();
+}
+
+/// The `[filter]` table of the config file.
+#[derive(Deserialize)]
+#[serde(default)]
+struct FilterConfig {
+    on: bool,
+    /// Multiplier on how hard the filter pushes the water around.
+    strength: f32,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            on: true,
+            strength: 1.,
+        }
+    }
+}
+
+#[derive(Component)]
+struct Filter;
+
+#[derive(Event, Default)]
+pub struct FilterEvent;
+
+/// The water circulated by the filter: out along the surface, down the far wall, back across
+/// the gravel and up again at the intake.
+#[derive(Resource)]
+pub struct Current {
+    on: bool,
+    strength: f32,
+    /// How far the current has picked up, from still to full flow.
+    flow: f32,
+}
+
+impl Current {
+    /// Velocity of the water at a point in the tank, in world units per second.
+    pub fn at(&self, point: Vec3) -> Vec3 {
+        if self.flow <= 0. {
+            return Vec3::ZERO;
+        }
+
+        let width = TANK_RIGHT - TANK_LEFT;
+        let height = WATER_SURFACE_HEIGHT - TANK_BOTTOM;
+        let x = ((point.x - TANK_LEFT) / width).clamp(0., 1.) * PI;
+        let y = ((point.y - TANK_BOTTOM) / height).clamp(0., 1.) * PI;
+
+        // a single circulating cell, which never piles water up anywhere.
+        let circulation =
+            Vec3::new(x.sin() * y.cos(), -x.cos() * y.sin() * height / width, 0.) * CURRENT_SPEED;
+
+        let outflow = Vec3::NEG_X
+            * OUTFLOW_SPEED
+            * (-(point - FILTER_POSITION).xy().length_squared() / OUTFLOW_SPREAD).exp();
+
+        (circulation + outflow) * self.strength * self.flow
+    }
+}
+
+fn setup_filter_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.2, 0.22, 0.24),
+        perceptual_roughness: 0.6,
+        ..default()
+    });
+
+    commands.spawn((
+        Filter,
+        Mesh3d(meshes.add(Cuboid::new(0.25, 0.5, 0.2))),
+        MeshMaterial3d(material.clone()),
+        Transform::from_translation(FILTER_POSITION),
+    ));
+
+    let intake_length = FILTER_POSITION.y - FILTER_INTAKE_BOTTOM;
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(0.03, intake_length))),
+        MeshMaterial3d(material),
+        Transform::from_translation(
+            FILTER_POSITION.with_y(FILTER_INTAKE_BOTTOM + intake_length / 2.),
+        ),
+    ));
+}
+
+fn toggle_filter_system(
+    mut filter_events: EventReader<FilterEvent>,
+    mut current: ResMut<Current>,
+    mut sfx_events: EventWriter<SfxEvent>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for _ in filter_events.read() {
+        current.on = !current.on;
+
+        let (sfx, message) = if current.on {
+            (Sfx::ClickOn, "filter on")
+        } else {
+            (Sfx::ClickOff, "filter off")
+        };
+        sfx_events.write(SfxEvent(sfx, Some(FILTER_POSITION.x)));
+        toast_events.write(ToastEvent(message.into()));
+    }
+}
+
+fn ramp_flow_system(time: Res<Time>, mut current: ResMut<Current>) {
+    let target = if current.on { 1. } else { 0. };
+    let step = FLOW_RAMP_RATE * time.delta_secs();
+    current.flow += (target - current.flow).clamp(-step, step);
+}
+
+/// Arrows across the middle of the tank showing which way and how fast the water is moving.
+fn draw_current_system(mut gizmos: Gizmos, current: Res<Current>) {
+    let columns = ((TANK_RIGHT - TANK_LEFT) / ARROW_SPACING) as u32;
+    let rows = ((WATER_SURFACE_HEIGHT - TANK_BOTTOM) / ARROW_SPACING) as u32;
+
+    for column in 0..columns {
+        for row in 0..rows {
+            let point = Vec3::new(
+                TANK_LEFT + (column as f32 + 0.5) * ARROW_SPACING,
+                TANK_BOTTOM + (row as f32 + 0.5) * ARROW_SPACING,
+                0.,
+            );
+            let velocity = current.at(point);
+            if velocity.length() < 0.005 {
+                continue;
+            }
+
+            gizmos.arrow(
+                point,
+                point + velocity * ARROW_SCALE,
+                Color::srgb(1., 0.8, 0.3),
+            );
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use rand_chacha::rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    camera::LightLevel,
+    config::{config_section, ConfigProblems},
+    creatures::fish_systems::{FishMaterials, FISH_EMISSIVE, FISH_SATURATION},
+    general::state_dir,
+    pellets::{PelletEvent, PelletRng, PELLET_SURFACE_HEIGHT},
+    toast::ToastEvent,
+};
+
+const FOCUS_STATE_FILE: &str = "focus.toml";
+const FOCUS_LIGHT_LEVEL: f32 = 0.35;
+const FOCUS_LIGHT_FADE_RATE: f32 = 0.3;
+const BREAK_ACTIVITY: f32 = 1.6;
+const BREAK_SATURATION: f32 = 0.8;
+/// Longest a focus session or break can be set to last, a whole day.
+const PHASE_MINUTES_MAX: f32 = 24. * 60.;
+
+pub(super) fn plugin(app: &mut App) {
+    let mut config: FocusConfig = config_section(app, "focus");
+    let problems = config.validate();
+    app.world_mut().resource_mut::<ConfigProblems>().extend(
+        problems
+            .into_iter()
+            .map(|problem| format!("[focus]: {problem}")),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            toggle_focus_system,
+            advance_focus_system,
+            focus_light_system,
+            focus_colors_system,
+        )
+            .chain(),
+    )
+    .insert_resource(FocusSession::load(config))
+    .add_event::<FocusEvent>();
+}
+
+/// The `[focus]` table of the config file.
+#[derive(Deserialize)]
+#[serde(default)]
+struct FocusConfig {
+    focus_minutes: f32,
+    break_minutes: f32,
+    reward_pellets: u32,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self {
+            focus_minutes: 25.,
+            break_minutes: 5.,
+            reward_pellets: 6,
+        }
+    }
+}
+
+impl FocusConfig {
+    /// Pulls the phase lengths back into a usable range, describing whatever had to change.
+    fn validate(&mut self) -> Vec<String> {
+        let defaults = Self::default();
+        let mut problems = Vec::new();
+
+        for (name, minutes, default) in [
+            (
+                "focus_minutes",
+                &mut self.focus_minutes,
+                defaults.focus_minutes,
+            ),
+            (
+                "break_minutes",
+                &mut self.break_minutes,
+                defaults.break_minutes,
+            ),
+        ] {
+            if !minutes.is_finite() {
+                problems.push(format!("{name} must be a number, using {default}"));
+                *minutes = default;
+            } else if !(0. ..=PHASE_MINUTES_MAX).contains(minutes) {
+                let clamped = minutes.clamp(0., PHASE_MINUTES_MAX);
+                problems.push(format!(
+                    "{name} must be from 0 to {PHASE_MINUTES_MAX}, using {clamped}"
+                ));
+                *minutes = clamped;
+            }
+        }
+
+        problems
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusPhase {
+    #[default]
+    Idle,
+    Focus,
+    Break,
+}
+
+/// What gets written to the state dir, so that a session carries on across restarts.
+#[derive(Serialize, Deserialize, Default)]
+struct FocusState {
+    phase: FocusPhase,
+    /// When the current phase started, in seconds since the unix epoch.
+    started: u64,
+}
+
+/// A work/break cycle, timed by the wall clock rather than the app so that time spent
+/// closed still counts.
+#[derive(Resource)]
+pub struct FocusSession {
+    config: FocusConfig,
+    state: FocusState,
+}
+
+impl FocusSession {
+    fn load(config: FocusConfig) -> Self {
+        let state = state_dir()
+            .and_then(|dir| std::fs::read_to_string(dir.join(FOCUS_STATE_FILE)).ok())
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        Self { config, state }
+    }
+
+    fn save(&self) {
+        let Some(dir) = state_dir() else {
+            return;
+        };
+
+        if let Ok(contents) = toml::to_string(&self.state) {
+            let _ = std::fs::create_dir_all(&dir)
+                .and_then(|_| std::fs::write(dir.join(FOCUS_STATE_FILE), contents));
+        }
+    }
+
+    fn begin(&mut self, phase: FocusPhase, started: u64) {
+        self.state = FocusState { phase, started };
+        self.save();
+    }
+
+    pub fn phase(&self) -> FocusPhase {
+        self.state.phase
+    }
+
+    fn length(&self) -> Duration {
+        let minutes = match self.state.phase {
+            FocusPhase::Idle => 0.,
+            FocusPhase::Focus => self.config.focus_minutes,
+            FocusPhase::Break => self.config.break_minutes,
+        };
+
+        Duration::from_secs_f32(minutes * 60.)
+    }
+
+    fn elapsed(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.state.started))
+    }
+
+    /// How far through the current phase, from zero to one.
+    pub fn progress(&self) -> f32 {
+        let length = self.length().as_secs_f32();
+        if length <= 0. {
+            return 1.;
+        }
+
+        (self.elapsed().as_secs_f32() / length).clamp(0., 1.)
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.length().saturating_sub(self.elapsed())
+    }
+
+    /// Multiplier on how lively the fish are, who perk up during breaks.
+    pub fn activity(&self) -> f32 {
+        if self.state.phase == FocusPhase::Break {
+            BREAK_ACTIVITY
+        } else {
+            1.
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Starts a focus session, or abandons the one underway.
+#[derive(Event, Default)]
+pub struct FocusEvent;
+
+fn toggle_focus_system(
+    mut focus_events: EventReader<FocusEvent>,
+    mut session: ResMut<FocusSession>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for _ in focus_events.read() {
+        match session.phase() {
+            FocusPhase::Idle | FocusPhase::Break => {
+                session.begin(FocusPhase::Focus, now());
+                toast_events.write(ToastEvent(format!(
+                    "focusing for {} minutes",
+                    session.config.focus_minutes
+                )));
+            }
+            FocusPhase::Focus => {
+                session.begin(FocusPhase::Idle, 0);
+                toast_events.write(ToastEvent("focus session abandoned".into()));
+            }
+        }
+    }
+}
+
+fn advance_focus_system(
+    mut session: ResMut<FocusSession>,
+    mut pellet_rng: ResMut<PelletRng>,
+    mut pellet_events: EventWriter<PelletEvent>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    if session.phase() == FocusPhase::Idle || session.remaining() > Duration::ZERO {
+        return;
+    }
+
+    match session.phase() {
+        FocusPhase::Focus => {
+            // the break starts when the focus session ended, even if that was while closed.
+            let started = session.state.started + session.length().as_secs();
+            session.begin(FocusPhase::Break, started);
+
+            for _ in 0..session.config.reward_pellets {
+                let x = pellet_rng.next_u32() as f32 / u32::MAX as f32 * 3.4 - 1.7;
+                pellet_events.write(PelletEvent(
+                    Transform::from_xyz(x, PELLET_SURFACE_HEIGHT, 0.),
+                    None,
+                ));
+            }
+
+            toast_events.write(ToastEvent("focus session done, time for a break".into()));
+        }
+        FocusPhase::Break => {
+            session.begin(FocusPhase::Idle, 0);
+            toast_events.write(ToastEvent("break's over".into()));
+        }
+        FocusPhase::Idle => {}
+    }
+}
+
+fn focus_light_system(time: Res<Time>, session: Res<FocusSession>, mut level: ResMut<LightLevel>) {
+    let target = if session.phase() == FocusPhase::Focus {
+        FOCUS_LIGHT_LEVEL
+    } else {
+        1.
+    };
+
+    if **level != target {
+        let step = FOCUS_LIGHT_FADE_RATE * time.delta_secs();
+        **level += (target - **level).clamp(-step, step);
+    }
+}
+
+fn focus_colors_system(
+    session: Res<FocusSession>,
+    fish_materials: Res<FishMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut applied: Local<Option<FocusPhase>>,
+) {
+    if *applied == Some(session.phase()) {
+        return;
+    }
+    *applied = Some(session.phase());
+
+    let saturation = if session.phase() == FocusPhase::Break {
+        BREAK_SATURATION
+    } else {
+        FISH_SATURATION
+    };
+
+    for handle in fish_materials.iter() {
+        if let Some(material) = materials.get_mut(handle) {
+            material.base_color = Hsla::from(material.base_color)
+                .with_saturation(saturation)
+                .into();
+            material.emissive = material.base_color.to_linear() * FISH_EMISSIVE;
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    collections::HashMap,
+    io::stdout,
+    time::{Duration, Instant},
+};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_ratatui::{event::FocusEvent as TerminalFocusEvent, RatatuiContext};
+use crossterm::event::{DisableFocusChange, EnableFocusChange};
+use serde::Deserialize;
+
+use crate::{
+    config::config_section, creatures::behavior::CreatureBehavior, pellets::Pellet,
+    toast::ToastEvent,
+};
+
+const BATTERY_SAMPLE_INTERVAL_SECONDS: f32 = 30.;
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+/// Speed on screen, in world units per real second, at which the tank gets the full frame rate.
+const FAST_MOTION_SPEED: f32 = 1.2;
+/// Fraction of the way from the minimum to the maximum frame rate used when all is calm.
+const RESTING_FPS_FRACTION: f32 = 0.3;
+const ECO_MAX_FPS: f32 = 30.;
+/// How quickly the frame rate eases towards its target, per second, so that frame times
+/// never jump all at once.
+const FPS_EASING_RATE: f32 = 2.;
+
+pub(super) fn plugin(app: &mut App) {
+    let config: FrameRateConfig = config_section(app, "framerate");
+
+    app.add_systems(Startup, (enable_focus_reporting_system, limit_delta_system))
+        .add_systems(
+            Update,
+            (
+                terminal_focus_system,
+                sample_battery_system.run_if(on_timer(Duration::from_secs_f32(
+                    BATTERY_SAMPLE_INTERVAL_SECONDS,
+                ))),
+                measure_motion_system,
+                toggle_eco_system,
+                choose_frame_rate_system,
+            )
+                .chain(),
+        )
+        .add_systems(Last, pace_frame_system)
+        .insert_resource(FramePacing {
+            eco: config.eco,
+            target_fps: config.max_fps,
+            config,
+            focused: true,
+            on_battery: on_battery(),
+            motion: 0.,
+        })
+        .add_event::<EcoEvent>();
+}
+
+/// The `[framerate]` table of the config file.
+#[derive(Deserialize)]
+#[serde(default)]
+struct FrameRateConfig {
+    min_fps: f32,
+    max_fps: f32,
+    /// Start in eco mode, which caps the frame rate low and is switched on anyway on battery.
+    eco: bool,
+}
+
+impl Default for FrameRateConfig {
+    fn default() -> Self {
+        Self {
+            min_fps: 10.,
+            max_fps: 90.,
+            eco: false,
+        }
+    }
+}
+
+/// How fast frames are drawn, which drops whenever nobody would notice the difference.
+#[derive(Resource)]
+pub struct FramePacing {
+    config: FrameRateConfig,
+    eco: bool,
+    focused: bool,
+    on_battery: bool,
+    /// Speed of the fastest thing in the tank.
+    motion: f32,
+    target_fps: f32,
+}
+
+impl FramePacing {
+    fn min_fps(&self) -> f32 {
+        self.config.min_fps.clamp(1., self.max_fps())
+    }
+
+    fn max_fps(&self) -> f32 {
+        let max = self.config.max_fps.max(1.);
+        if self.eco || self.on_battery {
+            max.min(ECO_MAX_FPS)
+        } else {
+            max
+        }
+    }
+
+    fn desired_fps(&self) -> f32 {
+        let (min, max) = (self.min_fps(), self.max_fps());
+        if !self.focused {
+            return min;
+        }
+
+        let busy = (self.motion / FAST_MOTION_SPEED).clamp(0., 1.);
+        let resting = min + (max - min) * RESTING_FPS_FRACTION;
+
+        resting + (max - resting) * busy
+    }
+}
+
+/// Switches eco mode on or off.
+#[derive(Event, Default)]
+pub struct EcoEvent;
+
+/// Turns focus reporting back off when the app closes, so the shell isn't sent focus escapes.
+#[derive(Resource)]
+struct FocusReporting;
+
+impl Drop for FocusReporting {
+    fn drop(&mut self) {
+        let _ = crossterm::execute!(stdout(), DisableFocusChange);
+    }
+}
+
+fn enable_focus_reporting_system(mut commands: Commands, ratatui: Option<Res<RatatuiContext>>) {
+    if ratatui.is_some() && crossterm::execute!(stdout(), EnableFocusChange).is_ok() {
+        commands.insert_resource(FocusReporting);
+    }
+}
+
+/// Frames as slow as the minimum frame rate still advance the clock by the whole frame, but
+/// anything slower (a suspended laptop, say) is cut short rather than teleporting the fish.
+fn limit_delta_system(pacing: Res<FramePacing>, mut time: ResMut<Time<Virtual>>) {
+    time.set_max_delta(Duration::from_secs_f32(2. / pacing.min_fps()));
+}
+
+fn terminal_focus_system(
+    mut focus_events: EventReader<TerminalFocusEvent>,
+    mut pacing: ResMut<FramePacing>,
+) {
+    for event in focus_events.read() {
+        pacing.focused = matches!(event, TerminalFocusEvent::Gained);
+    }
+}
+
+fn sample_battery_system(mut pacing: ResMut<FramePacing>) {
+    pacing.on_battery = on_battery();
+}
+
+/// Whether there is a battery and it is discharging, going by sysfs on linux.
+fn on_battery() -> bool {
+    let Ok(supplies) = std::fs::read_dir(POWER_SUPPLY_DIR) else {
+        return false;
+    };
+
+    supplies.flatten().any(|supply| {
+        let read = |name: &str| std::fs::read_to_string(supply.path().join(name)).ok();
+
+        read("type").is_some_and(|kind| kind.trim() == "Battery")
+            && read("status").is_some_and(|status| status.trim() == "Discharging")
+    })
+}
+
+fn measure_motion_system(
+    time: Res<Time<Real>>,
+    moving: Query<(Entity, &Transform), Or<(With<CreatureBehavior>, With<Pellet>)>>,
+    mut pacing: ResMut<FramePacing>,
+    mut previous: Local<HashMap<Entity, Vec3>>,
+) {
+    let delta = time.delta_secs();
+    if delta <= 0. {
+        return;
+    }
+
+    let mut fastest = 0_f32;
+    let mut positions = HashMap::with_capacity(previous.len());
+    for (entity, transform) in moving.iter() {
+        if let Some(position) = previous.get(&entity) {
+            fastest = fastest.max(position.distance(transform.translation) / delta);
+        }
+        positions.insert(entity, transform.translation);
+    }
+
+    pacing.motion = fastest;
+    *previous = positions;
+}
+
+fn toggle_eco_system(
+    mut eco_events: EventReader<EcoEvent>,
+    mut pacing: ResMut<FramePacing>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for _ in eco_events.read() {
+        pacing.eco = !pacing.eco;
+        toast_events.write(ToastEvent(
+            match (pacing.eco, pacing.on_battery) {
+                (true, _) => "eco mode on",
+                (false, true) => "eco mode stays on while on battery",
+                (false, false) => "eco mode off",
+            }
+            .into(),
+        ));
+    }
+}
+
+fn choose_frame_rate_system(real_time: Res<Time<Real>>, mut pacing: ResMut<FramePacing>) {
+    let desired = pacing.desired_fps();
+
+    // the frame rate falls gently but jumps straight up, so that movement never lags behind.
+    pacing.target_fps = if desired > pacing.target_fps {
+        desired
+    } else {
+        let step = pacing.target_fps * FPS_EASING_RATE * real_time.delta_secs();
+        (pacing.target_fps - step).max(desired)
+    };
+}
+
+/// Sleeps off whatever is left of the frame, which stands in for the schedule runner's own
+/// fixed wait.
+fn pace_frame_system(pacing: Res<FramePacing>, mut last_frame: Local<Option<Instant>>) {
+    let frame = Duration::from_secs_f32(1. / pacing.target_fps.max(1.));
+
+    if let Some(last_frame) = *last_frame {
+        if let Some(remaining) = frame.checked_sub(last_frame.elapsed()) {
+            std::thread::sleep(remaining);
+        }
+    }
+
+    *last_frame = Some(Instant::now());
+}
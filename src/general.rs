@@ -1,6 +1,6 @@
-use bevy::prelude::*;
+use std::path::PathBuf;
 
-use crate::Flags;
+use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(PostUpdate, deferred_despawn_system);
@@ -20,8 +20,26 @@ fn deferred_despawn_system(
     }
 }
 
-pub fn play_sfx(commands: &mut Commands, source: &Handle<AudioSource>, flags: &Flags) {
-    if !flags.muted {
-        commands.spawn((AudioPlayer::new(source.clone()), PlaybackSettings::DESPAWN));
-    }
+/// Directory for the little bits of state kept between runs, following the XDG base directory spec.
+pub fn state_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".local").join("state"))
+        })
+        .map(|dir| dir.join("lifecycler"))
+}
+
+/// Directory holding the config file, following the XDG base directory spec.
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".config"))
+        })
+        .map(|dir| dir.join("lifecycler"))
 }
@@ -0,0 +1,256 @@
+use std::io::{self, Cursor, Write};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bevy::prelude::*;
+use bevy_ratatui::RatatuiContext;
+use image::{imageops::FilterType, DynamicImage, ImageFormat};
+use ratatui::{backend::Backend, buffer::Buffer, layout::Rect, style::Color};
+
+/// Image id the tank is sent to kitty under, so that each frame replaces the last.
+const KITTY_IMAGE_ID: u32 = 1;
+/// Longest piece of base64 kitty accepts in a single escape sequence.
+const KITTY_CHUNK_SIZE: usize = 4096;
+/// Pixels per cell to assume when the terminal doesn't say.
+const FALLBACK_CELL_SIZE: (u32, u32) = (8, 16);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        clear_image_system.run_if(resource_changed::<ImageOutput>),
+    )
+    .init_resource::<ImageOutput>();
+}
+
+/// Ways of sending real pixels to a terminal rather than drawing with characters.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImageProtocol {
+    Kitty,
+    Sixel,
+}
+
+impl ImageProtocol {
+    /// Goes by the environment, since asking the terminal itself would race the input reader
+    /// for the reply.
+    pub fn detect() -> Option<Self> {
+        let term = std::env::var("TERM").unwrap_or_default();
+        let program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+
+        // multiplexers only pass images through when set up to, and place them badly anyway.
+        if std::env::var_os("TMUX").is_some() || term.starts_with("screen") {
+            return None;
+        }
+
+        if std::env::var_os("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term.contains("ghostty")
+            || matches!(program.as_str(), "WezTerm" | "ghostty")
+        {
+            return Some(Self::Kitty);
+        }
+
+        if ["foot", "mlterm", "contour", "yaft"]
+            .iter()
+            .any(|sixel_term| term.starts_with(sixel_term))
+        {
+            return Some(Self::Sixel);
+        }
+
+        None
+    }
+}
+
+/// Whether the tank is being drawn as an image, and with which protocol.
+#[derive(Resource, Default)]
+pub struct ImageOutput {
+    pub protocol: Option<ImageProtocol>,
+    pub active: bool,
+}
+
+impl ImageOutput {
+    /// The protocol to draw the tank with this frame, if it is being drawn as an image.
+    pub fn drawing(&self) -> Option<ImageProtocol> {
+        self.protocol.filter(|_| self.active)
+    }
+}
+
+/// Where the camera image sits in the terminal, matching the square tank the character modes
+/// draw.
+pub fn tank_area(area: Rect) -> Rect {
+    let side = area.width.min(area.height * 2);
+
+    Rect::new(
+        (area.width - side) / 2,
+        (area.height * 2).saturating_sub(area.width) / 4,
+        side,
+        side / 2,
+    )
+}
+
+/// Draws the camera image over the tank, after the cells of a frame have gone out.
+///
+/// Kitty puts the image underneath the text, so the overlays show through on their own. Sixel
+/// images cover whatever is there, so every cell with something in it is drawn again on top.
+pub fn draw_image(
+    ratatui: &mut RatatuiContext,
+    protocol: ImageProtocol,
+    image: &DynamicImage,
+    buffer: &Buffer,
+) -> io::Result<()> {
+    let area = tank_area(buffer.area);
+    if area.is_empty() {
+        return Ok(());
+    }
+
+    let backend = ratatui.backend_mut();
+    backend.set_cursor_position((area.x, area.y))?;
+
+    match protocol {
+        ImageProtocol::Kitty => write_kitty(backend, image, area)?,
+        ImageProtocol::Sixel => {
+            write_sixel(backend, image, area)?;
+
+            let covered = buffer
+                .content
+                .iter()
+                .enumerate()
+                .filter_map(|(index, cell)| {
+                    let (x, y) = buffer.pos_of(index);
+                    let blank = cell.symbol() == " " && cell.bg == Color::Reset;
+                    (!blank && area.contains((x, y).into())).then_some((x, y, cell))
+                });
+            backend.draw(covered)?;
+        }
+    }
+
+    Backend::flush(backend)
+}
+
+/// Sends the image as a png, scaled by the terminal to fill the tank's cells.
+fn write_kitty(out: &mut impl Write, image: &DynamicImage, area: Rect) -> io::Result<()> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(io::Error::other)?;
+    let payload = STANDARD.encode(png);
+
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(index + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+
+        // the first piece carries the keys: under the text, and leaving the cursor be.
+        if index == 0 {
+            write!(
+                out,
+                "\x1b_Ga=T,f=100,i={KITTY_IMAGE_ID},p=1,c={},r={},z=-1,C=1,q=2,m={more};{chunk}\x1b\\",
+                area.width, area.height,
+            )?;
+        } else {
+            write!(out, "\x1b_Gm={more};{chunk}\x1b\\")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends the image as sixels, scaled to the size of the tank's cells in pixels.
+fn write_sixel(out: &mut impl Write, image: &DynamicImage, area: Rect) -> io::Result<()> {
+    let (cell_width, cell_height) = crossterm::terminal::window_size()
+        .ok()
+        .filter(|size| size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0)
+        .map_or(FALLBACK_CELL_SIZE, |size| {
+            (
+                (size.width / size.columns) as u32,
+                (size.height / size.rows) as u32,
+            )
+        });
+
+    let (width, height) = (
+        area.width as u32 * cell_width,
+        area.height as u32 * cell_height,
+    );
+    let pixels = image
+        .resize_exact(width, height, FilterType::Triangle)
+        .to_rgb8();
+
+    // the colors of a 6x6x6 cube, which keeps finding a pixel's register down to arithmetic.
+    let register = |pixel: &image::Rgb<u8>| {
+        let [r, g, b] = pixel.0.map(|channel| (channel as u32 * 5 + 127) / 255);
+        (r * 36 + g * 6 + b) as usize
+    };
+
+    write!(out, "\x1bPq\"1;1;{width};{height}")?;
+    for index in 0..216 {
+        let (r, g, b) = (index / 36, index / 6 % 6, index % 6);
+        write!(out, "#{index};2;{};{};{}", r * 20, g * 20, b * 20)?;
+    }
+
+    let mut band = vec![[0u8; 216]; width as usize];
+    for top in (0..height).step_by(6) {
+        for column in band.iter_mut() {
+            *column = [0; 216];
+        }
+
+        for row in 0..6.min(height - top) {
+            for x in 0..width {
+                let pixel = pixels.get_pixel(x, top + row);
+                band[x as usize][register(pixel)] |= 1 << row;
+            }
+        }
+
+        let mut first = true;
+        for color in 0..216 {
+            if band.iter().all(|column| column[color] == 0) {
+                continue;
+            }
+
+            if !first {
+                write!(out, "$")?;
+            }
+            first = false;
+            write!(out, "#{color}")?;
+
+            // runs of the same sixel are sent as a count and the sixel once.
+            let mut columns = band.iter().map(|column| column[color]).peekable();
+            while let Some(sixel) = columns.next() {
+                let mut run = 1;
+                while columns.next_if_eq(&sixel).is_some() {
+                    run += 1;
+                }
+
+                let character = (sixel + 63) as char;
+                if run > 3 {
+                    write!(out, "!{run}{character}")?;
+                } else {
+                    for _ in 0..run {
+                        write!(out, "{character}")?;
+                    }
+                }
+            }
+        }
+
+        write!(out, "-")?;
+    }
+
+    write!(out, "\x1b\\")
+}
+
+/// Takes the image away again when switching back to characters, and has every cell redrawn
+/// over whatever was left behind.
+fn clear_image_system(output: Res<ImageOutput>, ratatui: Option<ResMut<RatatuiContext>>) {
+    let Some(mut ratatui) = ratatui else {
+        return;
+    };
+    if output.is_added() || output.active {
+        return;
+    }
+
+    if output.protocol == Some(ImageProtocol::Kitty) {
+        let _ = write!(
+            ratatui.backend_mut(),
+            "\x1b_Ga=d,d=I,i={KITTY_IMAGE_ID},q=2\x1b\\"
+        );
+    }
+
+    let _ = ratatui.clear();
+}
@@ -1,47 +1,226 @@
 use bevy::prelude::*;
 use bevy_ratatui::event::{KeyEvent, MouseEvent};
 use bevy_ratatui_camera::{RatatuiCameraLastArea, RatatuiCameraWidget};
-use crossterm::event::{KeyCode, KeyEventKind, MouseButton, MouseEventKind};
+use crossterm::event::{KeyEventKind, MouseButton, MouseEventKind};
+use rand::RngCore;
 
-use crate::{camera::DaylightEvent, pellets::PelletEvent, Flags};
+use crate::{
+    audio::VolumeEvent,
+    bubbles::BubbleStoneEvent,
+    camera::DaylightEvent,
+    capture::CaptureEvent,
+    creatures::{
+        illness::MedicateEvent, personality::InspectEvent, population::PopulationPolicyEvent,
+    },
+    filter::FilterEvent,
+    focus::FocusEvent,
+    framerate::EcoEvent,
+    keymap::{Action, Keymap},
+    pellets::{PelletEvent, PelletRng, PELLET_SURFACE_HEIGHT},
+    render::CycleRenderEvent,
+    simulation::SpeedEvent,
+    temperature::HeaterEvent,
+    Flags,
+};
 
 const DRAGS_PER_EVENT: u32 = 2;
+const CURSOR_IDLE_SECONDS: f32 = 5.;
+const SPRINKLE_PELLETS: u32 = 8;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(PreUpdate, (handle_keyboard_system, handle_mouse_system))
-        .init_resource::<DragThreshold>();
+    app.add_systems(
+        PreUpdate,
+        (
+            handle_keyboard_system,
+            handle_mouse_system,
+            keyboard_cursor_system,
+        )
+            .chain(),
+    )
+    .init_resource::<DragThreshold>()
+    .init_resource::<KeyboardCursor>()
+    .add_event::<CursorEvent>();
 }
 
 #[derive(Resource, Default, Deref, DerefMut)]
 pub struct DragThreshold(u32);
 
+/// A crosshair over the tank for feeding without a mouse, in terminal cells.
+#[derive(Resource)]
+pub struct KeyboardCursor {
+    pub position: Option<IVec2>,
+    idle: Timer,
+}
+
+impl Default for KeyboardCursor {
+    fn default() -> Self {
+        Self {
+            position: None,
+            idle: Timer::from_seconds(CURSOR_IDLE_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+impl KeyboardCursor {
+    /// The cursor only shows while it is in use, fading out once left idle.
+    pub fn visible_position(&self) -> Option<IVec2> {
+        self.position.filter(|_| !self.idle.finished())
+    }
+}
+
+#[derive(Event)]
+pub enum CursorEvent {
+    Move(IVec2),
+    Feed,
+    Sprinkle,
+    PlaceStone,
+}
+
 fn handle_keyboard_system(
     mut ratatui_events: EventReader<KeyEvent>,
+    keymap: Res<Keymap>,
     mut exit: EventWriter<AppExit>,
     mut flags: ResMut<Flags>,
     mut daylight_event: EventWriter<DaylightEvent>,
+    mut population_event: EventWriter<PopulationPolicyEvent>,
+    mut inspect_event: EventWriter<InspectEvent>,
+    mut medicate_event: EventWriter<MedicateEvent>,
+    (mut heater_event, mut filter_event): (EventWriter<HeaterEvent>, EventWriter<FilterEvent>),
+    mut cursor_event: EventWriter<CursorEvent>,
+    mut focus_event: EventWriter<FocusEvent>,
+    mut render_event: EventWriter<CycleRenderEvent>,
+    mut capture_event: EventWriter<CaptureEvent>,
+    mut eco_event: EventWriter<EcoEvent>,
+    mut speed_event: EventWriter<SpeedEvent>,
+    mut volume_event: EventWriter<VolumeEvent>,
 ) {
     for key_event in ratatui_events.read() {
         match key_event.kind {
-            KeyEventKind::Press | KeyEventKind::Repeat => match key_event.code {
-                KeyCode::Char('q') => {
-                    exit.write_default();
-                }
+            KeyEventKind::Press | KeyEventKind::Repeat => {
+                match keymap.action_for(key_event.code, key_event.modifiers) {
+                    Some(Action::Quit) => {
+                        exit.write_default();
+                    }
 
-                KeyCode::Char('d') => {
-                    flags.debug = !flags.debug;
-                }
+                    Some(Action::ToggleHelp) => {
+                        flags.help = !flags.help;
+                    }
 
-                KeyCode::Char('m') => {
-                    flags.muted = !flags.muted;
-                }
+                    Some(Action::ToggleDebug) => {
+                        flags.debug = !flags.debug;
+                    }
 
-                KeyCode::Char(' ') => {
-                    daylight_event.write_default();
-                }
+                    Some(Action::ToggleMute) => {
+                        flags.muted = !flags.muted;
+                    }
+
+                    Some(Action::VolumeUp) => {
+                        volume_event.write(VolumeEvent::Up);
+                    }
+
+                    Some(Action::VolumeDown) => {
+                        volume_event.write(VolumeEvent::Down);
+                    }
+
+                    Some(Action::ToggleStats) => {
+                        flags.stats = !flags.stats;
+                    }
+
+                    Some(Action::ToggleFocus) => {
+                        focus_event.write_default();
+                    }
+
+                    Some(Action::ToggleEco) => {
+                        eco_event.write_default();
+                    }
+
+                    Some(Action::ToggleFilter) => {
+                        filter_event.write_default();
+                    }
+
+                    Some(Action::SpeedUp) => {
+                        speed_event.write(SpeedEvent::Faster);
+                    }
+
+                    Some(Action::SlowDown) => {
+                        speed_event.write(SpeedEvent::Slower);
+                    }
+
+                    Some(Action::TogglePause) => {
+                        speed_event.write(SpeedEvent::TogglePause);
+                    }
+
+                    Some(Action::Step) if flags.debug => {
+                        speed_event.write(SpeedEvent::Step);
+                    }
+
+                    Some(Action::CycleRender) => {
+                        render_event.write_default();
+                    }
+
+                    Some(Action::Snapshot) => {
+                        capture_event.write(CaptureEvent::Snapshot);
+                    }
+
+                    Some(Action::ToggleRecording) => {
+                        capture_event.write(CaptureEvent::ToggleRecording);
+                    }
+
+                    Some(Action::ToggleDaylight) => {
+                        daylight_event.write_default();
+                    }
+
+                    Some(Action::Inspect) if flags.debug => {
+                        inspect_event.write_default();
+                    }
+
+                    Some(Action::Medicate) => {
+                        medicate_event.write_default();
+                    }
+
+                    Some(Action::RaiseHeater) => {
+                        heater_event.write(HeaterEvent::Raise);
+                    }
+
+                    Some(Action::LowerHeater) => {
+                        heater_event.write(HeaterEvent::Lower);
+                    }
+
+                    Some(Action::CyclePopulation) => {
+                        population_event.write(PopulationPolicyEvent::Next);
+                    }
+
+                    Some(Action::CursorLeft) => {
+                        cursor_event.write(CursorEvent::Move(IVec2::new(-2, 0)));
+                    }
+
+                    Some(Action::CursorRight) => {
+                        cursor_event.write(CursorEvent::Move(IVec2::new(2, 0)));
+                    }
 
-                _ => {}
-            },
+                    Some(Action::CursorUp) => {
+                        cursor_event.write(CursorEvent::Move(IVec2::new(0, -1)));
+                    }
+
+                    Some(Action::CursorDown) => {
+                        cursor_event.write(CursorEvent::Move(IVec2::new(0, 1)));
+                    }
+
+                    Some(Action::Feed) => {
+                        cursor_event.write(CursorEvent::Feed);
+                    }
+
+                    Some(Action::Sprinkle) => {
+                        cursor_event.write(CursorEvent::Sprinkle);
+                    }
+
+                    Some(Action::PlaceStone) => {
+                        cursor_event.write(CursorEvent::PlaceStone);
+                    }
+
+                    _ => {}
+                }
+            }
             _ => {}
         }
     }
@@ -50,6 +229,7 @@ fn handle_keyboard_system(
 fn handle_mouse_system(
     mut events: EventReader<MouseEvent>,
     mut pellet_event: EventWriter<PelletEvent>,
+    mut stone_event: EventWriter<BubbleStoneEvent>,
     mut drag_threshold: ResMut<DragThreshold>,
     camera: Single<
         (
@@ -62,48 +242,129 @@ fn handle_mouse_system(
     >,
 ) {
     for event in events.read() {
-        let (camera, camera_transform, camera_widget, last_area) = *camera;
-
         match event.kind {
             MouseEventKind::Drag(MouseButton::Left) | MouseEventKind::Down(MouseButton::Left) => {
                 if **drag_threshold == 0 || event.kind == MouseEventKind::Down(MouseButton::Left) {
                     **drag_threshold = DRAGS_PER_EVENT;
 
-                    let ndc = camera_widget.cell_to_ndc(
-                        **last_area,
-                        IVec2::new(event.column as i32, event.row as i32),
-                    );
-
-                    let world_position = camera.ndc_to_world(camera_transform, ndc).unwrap();
-
-                    let viewport_position = camera
-                        .world_to_viewport(camera_transform, world_position)
-                        .unwrap();
-
-                    let ray = camera
-                        .viewport_to_world(camera_transform, viewport_position)
-                        .unwrap();
-
-                    let Some(intersect_distance) =
-                        ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Z))
+                    let Some(intersect) =
+                        cell_to_tank(*camera, IVec2::new(event.column as i32, event.row as i32))
                     else {
                         return;
                     };
 
-                    let intersect = ray.get_point(intersect_distance);
-
-                    if !(-1.9..1.9).contains(&intersect.x) {
-                        return;
-                    }
-
                     let world_transform = Transform::from_translation(intersect);
 
-                    pellet_event.write(PelletEvent(world_transform));
+                    pellet_event.write(PelletEvent(world_transform, None));
                 } else {
                     **drag_threshold -= 1;
                 }
             }
+            MouseEventKind::Down(MouseButton::Right) => {
+                if let Some(intersect) =
+                    cell_to_tank(*camera, IVec2::new(event.column as i32, event.row as i32))
+                {
+                    stone_event.write(BubbleStoneEvent(intersect.x));
+                }
+            }
             _ => {}
         }
     }
 }
+
+fn keyboard_cursor_system(
+    time: Res<Time<Real>>,
+    mut cursor_events: EventReader<CursorEvent>,
+    mut cursor: ResMut<KeyboardCursor>,
+    mut pellet_event: EventWriter<PelletEvent>,
+    mut stone_event: EventWriter<BubbleStoneEvent>,
+    mut pellet_rng: ResMut<PelletRng>,
+    camera: Single<
+        (
+            &Camera,
+            &GlobalTransform,
+            &RatatuiCameraWidget,
+            &RatatuiCameraLastArea,
+        ),
+        With<Camera>,
+    >,
+) {
+    cursor.idle.tick(time.delta());
+
+    let area = **camera.3;
+    let side = area.width.min(area.height * 2);
+    let min = IVec2::new(
+        (area.x + (area.width / 2).saturating_sub(side / 2)) as i32,
+        (area.y + (area.height * 2).saturating_sub(area.width) / 4) as i32,
+    );
+    let max = min + IVec2::new(side as i32 - 1, (side / 2) as i32 - 1).max(IVec2::ZERO);
+
+    for event in cursor_events.read() {
+        let position = cursor.position.unwrap_or((min + max) / 2);
+
+        match event {
+            CursorEvent::Move(step) => {
+                cursor.position = Some((position + *step).clamp(min, max));
+                cursor.idle.reset();
+            }
+
+            CursorEvent::Feed => {
+                cursor.position = Some(position);
+                cursor.idle.reset();
+
+                if let Some(intersect) = cell_to_tank(*camera, position) {
+                    pellet_event.write(PelletEvent(Transform::from_translation(intersect), None));
+                }
+            }
+
+            CursorEvent::PlaceStone => {
+                cursor.position = Some(position);
+                cursor.idle.reset();
+
+                if let Some(intersect) = cell_to_tank(*camera, position) {
+                    stone_event.write(BubbleStoneEvent(intersect.x));
+                }
+            }
+
+            CursorEvent::Sprinkle => {
+                for _ in 0..SPRINKLE_PELLETS {
+                    let x = pellet_rng.next_u32() as f32 / u32::MAX as f32 * 3.4 - 1.7;
+                    pellet_event.write(PelletEvent(
+                        Transform::from_xyz(x, PELLET_SURFACE_HEIGHT, 0.),
+                        None,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Projects a terminal cell onto the plane through the middle of the tank, where pellets are
+/// dropped in, if the cell is over the tank.
+fn cell_to_tank(
+    (camera, camera_transform, camera_widget, last_area): (
+        &Camera,
+        &GlobalTransform,
+        &RatatuiCameraWidget,
+        &RatatuiCameraLastArea,
+    ),
+    cell: IVec2,
+) -> Option<Vec3> {
+    let ndc = camera_widget.cell_to_ndc(**last_area, cell);
+
+    let world_position = camera.ndc_to_world(camera_transform, ndc)?;
+
+    let viewport_position = camera
+        .world_to_viewport(camera_transform, world_position)
+        .ok()?;
+
+    let ray = camera
+        .viewport_to_world(camera_transform, viewport_position)
+        .ok()?;
+
+    let intersect_distance = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Z))?;
+
+    let intersect = ray.get_point(intersect_distance);
+
+    (-1.9..1.9).contains(&intersect.x).then_some(intersect)
+}
@@ -0,0 +1,422 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use bevy::prelude::*;
+use crossterm::event::{KeyCode, KeyModifiers};
+use serde::Deserialize;
+
+use crate::config::{config_section, ConfigProblems};
+
+pub(super) fn plugin(app: &mut App) {
+    let keys = config_section(app, "keys");
+    let (keymap, problems) = Keymap::from_config(keys);
+
+    app.world_mut().resource_mut::<ConfigProblems>().extend(
+        problems
+            .into_iter()
+            .map(|problem| format!("[keys]: {problem}")),
+    );
+
+    app.insert_resource(keymap);
+}
+
+/// Everything the player can do from the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    ToggleHelp,
+    ToggleDebug,
+    ToggleMute,
+    ToggleStats,
+    ToggleDaylight,
+    Medicate,
+    RaiseHeater,
+    LowerHeater,
+    CyclePopulation,
+    Inspect,
+    CursorLeft,
+    CursorRight,
+    CursorUp,
+    CursorDown,
+    Feed,
+    Sprinkle,
+    ToggleFocus,
+    CycleRender,
+    Snapshot,
+    ToggleRecording,
+    ToggleEco,
+    SpeedUp,
+    SlowDown,
+    TogglePause,
+    Step,
+    VolumeUp,
+    VolumeDown,
+    PlaceStone,
+    ToggleFilter,
+}
+
+impl Action {
+    /// Every action, in the order they are listed in the help.
+    pub const ALL: [Self; 30] = [
+        Self::ToggleHelp,
+        Self::CursorLeft,
+        Self::CursorRight,
+        Self::CursorUp,
+        Self::CursorDown,
+        Self::Feed,
+        Self::Sprinkle,
+        Self::PlaceStone,
+        Self::ToggleFilter,
+        Self::ToggleDaylight,
+        Self::ToggleMute,
+        Self::VolumeUp,
+        Self::VolumeDown,
+        Self::ToggleStats,
+        Self::ToggleFocus,
+        Self::ToggleEco,
+        Self::SpeedUp,
+        Self::SlowDown,
+        Self::TogglePause,
+        Self::Medicate,
+        Self::RaiseHeater,
+        Self::LowerHeater,
+        Self::CyclePopulation,
+        Self::CycleRender,
+        Self::Snapshot,
+        Self::ToggleRecording,
+        Self::ToggleDebug,
+        Self::Inspect,
+        Self::Step,
+        Self::Quit,
+    ];
+
+    /// The name of the action in the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::ToggleHelp => "toggle_help",
+            Self::ToggleDebug => "toggle_debug",
+            Self::ToggleMute => "toggle_mute",
+            Self::ToggleStats => "toggle_stats",
+            Self::ToggleDaylight => "toggle_daylight",
+            Self::Medicate => "medicate",
+            Self::RaiseHeater => "raise_heater",
+            Self::LowerHeater => "lower_heater",
+            Self::CyclePopulation => "cycle_population",
+            Self::Inspect => "inspect",
+            Self::CursorLeft => "cursor_left",
+            Self::CursorRight => "cursor_right",
+            Self::CursorUp => "cursor_up",
+            Self::CursorDown => "cursor_down",
+            Self::Feed => "feed",
+            Self::Sprinkle => "sprinkle",
+            Self::ToggleFocus => "toggle_focus",
+            Self::CycleRender => "cycle_render",
+            Self::Snapshot => "snapshot",
+            Self::ToggleRecording => "toggle_recording",
+            Self::ToggleEco => "toggle_eco",
+            Self::SpeedUp => "speed_up",
+            Self::SlowDown => "slow_down",
+            Self::TogglePause => "toggle_pause",
+            Self::Step => "step",
+            Self::VolumeUp => "volume_up",
+            Self::VolumeDown => "volume_down",
+            Self::PlaceStone => "place_stone",
+            Self::ToggleFilter => "toggle_filter",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::ToggleHelp => "show/hide this help",
+            Self::ToggleDebug => "show/hide debug info",
+            Self::ToggleMute => "mute/unmute sound",
+            Self::ToggleStats => "show/hide statistics",
+            Self::ToggleDaylight => "toggle day/night",
+            Self::Medicate => "medicate the water",
+            Self::RaiseHeater => "raise the heater",
+            Self::LowerHeater => "lower the heater",
+            Self::CyclePopulation => "cycle population policies",
+            Self::Inspect => "inspect fish (debug mode)",
+            Self::CursorLeft => "move the feeding cursor left",
+            Self::CursorRight => "move the feeding cursor right",
+            Self::CursorUp => "move the feeding cursor up",
+            Self::CursorDown => "move the feeding cursor down",
+            Self::Feed => "dispense a pellet at the cursor",
+            Self::Sprinkle => "sprinkle pellets across the tank",
+            Self::ToggleFocus => "start/abandon a focus session",
+            Self::CycleRender => "cycle render modes",
+            Self::Snapshot => "save a snapshot of the tank",
+            Self::ToggleRecording => "start/stop recording",
+            Self::ToggleEco => "switch eco mode on/off",
+            Self::SpeedUp => "speed up the simulation",
+            Self::SlowDown => "slow down or pause the simulation",
+            Self::TogglePause => "pause/unpause the simulation",
+            Self::Step => "step while paused (debug mode)",
+            Self::VolumeUp => "turn the volume up",
+            Self::VolumeDown => "turn the volume down",
+            Self::PlaceStone => "place/remove a bubble stone at the cursor",
+            Self::ToggleFilter => "switch the filter on/off",
+        }
+    }
+}
+
+/// A key along with the modifiers that must be held, e.g. `ctrl+c`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    const fn key(character: char) -> Self {
+        Self::code(KeyCode::Char(character))
+    }
+
+    const fn code(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    /// Whether a key press triggers this binding. Shift is already reflected in the case of
+    /// characters, so it is only compared for other keys.
+    pub fn matches(&self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        let relevant = match code {
+            KeyCode::Char(_) => KeyModifiers::CONTROL | KeyModifiers::ALT,
+            _ => KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT,
+        };
+
+        self.code == code && self.modifiers & relevant == modifiers & relevant
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(binding: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = binding.split('+').collect();
+        // a lone or trailing "+" names the plus key itself.
+        if binding.ends_with('+') {
+            parts.truncate(parts.len().saturating_sub(2));
+            parts.push("+");
+        }
+
+        let Some((key, modifier_names)) = parts.split_last() else {
+            return Err(format!("empty key binding \"{binding}\""));
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for name in modifier_names {
+            modifiers |= match name.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier \"{name}\" in \"{binding}\"")),
+            };
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            name if name.len() > 1 && name.starts_with('f') => name[1..]
+                .parse()
+                .map(KeyCode::F)
+                .map_err(|_| format!("unknown key \"{key}\" in \"{binding}\""))?,
+            _ => {
+                let mut characters = key.chars();
+                match (characters.next(), characters.next()) {
+                    (Some(character), None) => KeyCode::Char(character),
+                    _ => return Err(format!("unknown key \"{key}\" in \"{binding}\"")),
+                }
+            }
+        };
+
+        // shift on a character is spelled as its uppercase form, which is what terminals report.
+        Ok(match code {
+            KeyCode::Char(character) if modifiers.contains(KeyModifiers::SHIFT) => Self {
+                code: KeyCode::Char(character.to_ascii_uppercase()),
+                modifiers: modifiers.difference(KeyModifiers::SHIFT),
+            },
+            code => Self { code, modifiers },
+        })
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(character) => write!(f, "{character}"),
+            code => write!(f, "{}", code.to_string().to_lowercase()),
+        }
+    }
+}
+
+/// Bindings used for any action the config file leaves out.
+pub const DEFAULT_BINDINGS: [(KeyBinding, Action); 36] = [
+    (KeyBinding::key('?'), Action::ToggleHelp),
+    (KeyBinding::key('h'), Action::CursorLeft),
+    (KeyBinding::code(KeyCode::Left), Action::CursorLeft),
+    (KeyBinding::key('l'), Action::CursorRight),
+    (KeyBinding::code(KeyCode::Right), Action::CursorRight),
+    (KeyBinding::key('k'), Action::CursorUp),
+    (KeyBinding::code(KeyCode::Up), Action::CursorUp),
+    (KeyBinding::key('j'), Action::CursorDown),
+    (KeyBinding::code(KeyCode::Down), Action::CursorDown),
+    (KeyBinding::key('f'), Action::Feed),
+    (KeyBinding::code(KeyCode::Enter), Action::Feed),
+    (KeyBinding::key('F'), Action::Sprinkle),
+    (KeyBinding::key('b'), Action::PlaceStone),
+    (KeyBinding::key('w'), Action::ToggleFilter),
+    (KeyBinding::key(' '), Action::ToggleDaylight),
+    (KeyBinding::key('m'), Action::ToggleMute),
+    (KeyBinding::key('+'), Action::VolumeUp),
+    (KeyBinding::key('='), Action::VolumeUp),
+    (KeyBinding::key('-'), Action::VolumeDown),
+    (KeyBinding::key('s'), Action::ToggleStats),
+    (KeyBinding::key('t'), Action::ToggleFocus),
+    (KeyBinding::key('e'), Action::ToggleEco),
+    (KeyBinding::key('>'), Action::SpeedUp),
+    (KeyBinding::key('<'), Action::SlowDown),
+    (KeyBinding::key('p'), Action::TogglePause),
+    (KeyBinding::key('c'), Action::Medicate),
+    (KeyBinding::key(']'), Action::RaiseHeater),
+    (KeyBinding::key('['), Action::LowerHeater),
+    (KeyBinding::key('P'), Action::CyclePopulation),
+    (KeyBinding::key('r'), Action::CycleRender),
+    (KeyBinding::key('x'), Action::Snapshot),
+    (KeyBinding::key('X'), Action::ToggleRecording),
+    (KeyBinding::key('d'), Action::ToggleDebug),
+    (KeyBinding::key('i'), Action::Inspect),
+    (KeyBinding::key('.'), Action::Step),
+    (KeyBinding::key('q'), Action::Quit),
+];
+
+/// Mouse controls, which are not remappable but belong in the help all the same.
+pub const MOUSE_BINDINGS: [(&str, &str); 2] = [
+    ("click/drag", "dispense a food pellet"),
+    ("right click", "place/remove a bubble stone"),
+];
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ConfigKeys {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// The key bindings in effect, with each key triggering at most one action.
+#[derive(Resource)]
+pub struct Keymap(Vec<(KeyBinding, Action)>);
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self(DEFAULT_BINDINGS.to_vec())
+    }
+}
+
+impl Keymap {
+    /// Builds the keymap from the `[keys]` table of the config file, where each action maps
+    /// to one key or a list of keys, e.g. `quit = ["q", "ctrl+c"]`. Returns whatever went
+    /// wrong alongside the keymap, which falls back to the defaults for anything it could not use.
+    fn from_config(mut keys: BTreeMap<String, ConfigKeys>) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+        let mut configured = Vec::new();
+
+        for action in Action::ALL {
+            let Some(keys) = keys.remove(action.name()) else {
+                continue;
+            };
+
+            let keys = match keys {
+                ConfigKeys::One(key) => vec![key],
+                ConfigKeys::Many(keys) => keys,
+            };
+
+            for key in keys {
+                match key.parse::<KeyBinding>() {
+                    Ok(binding) => configured.push((binding, action)),
+                    Err(problem) => problems.push(format!("{}: {problem}", action.name())),
+                }
+            }
+        }
+
+        for unknown in keys.keys() {
+            problems.push(format!("unknown action \"{unknown}\""));
+        }
+
+        // configured bindings take precedence, and defaults fill in for unconfigured actions.
+        let defaults = DEFAULT_BINDINGS.into_iter().filter(|(_, action)| {
+            !configured
+                .iter()
+                .any(|(_, configured_action)| configured_action == action)
+        });
+
+        let mut bindings: Vec<(KeyBinding, Action)> = Vec::new();
+        for (binding, action) in configured.iter().copied().chain(defaults) {
+            match bindings.iter().find(|(existing, _)| *existing == binding) {
+                Some((_, existing_action)) if *existing_action != action => {
+                    problems.push(format!(
+                        "\"{binding}\" is bound to both {} and {}, keeping {}",
+                        existing_action.name(),
+                        action.name(),
+                        existing_action.name()
+                    ));
+                }
+                Some(_) => {}
+                None => bindings.push((binding, action)),
+            }
+        }
+
+        (Self(bindings), problems)
+    }
+
+    pub fn action_for(&self, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        self.0
+            .iter()
+            .find(|(binding, _)| binding.matches(code, modifiers))
+            .map(|(_, action)| *action)
+    }
+
+    pub fn keys_for(&self, action: Action) -> impl Iterator<Item = &KeyBinding> {
+        self.0
+            .iter()
+            .filter(move |(_, bound)| *bound == action)
+            .map(|(binding, _)| binding)
+    }
+
+    /// Every bound action alongside all of its keys, for the help overlay.
+    pub fn help_entries(&self) -> Vec<(String, &'static str)> {
+        Action::ALL
+            .into_iter()
+            .filter_map(|action| {
+                let keys: Vec<String> = self.keys_for(action).map(ToString::to_string).collect();
+                (!keys.is_empty()).then(|| (keys.join("/"), action.description()))
+            })
+            .chain(
+                MOUSE_BINDINGS
+                    .into_iter()
+                    .map(|(input, description)| (input.to_string(), description)),
+            )
+            .collect()
+    }
+}
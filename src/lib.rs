@@ -7,60 +7,131 @@ use bevy::{
 };
 use bevy_atmosphere::plugin::AtmospherePlugin;
 use bevy_hanabi::HanabiPlugin;
-use bevy_ratatui::RatatuiPlugins;
+use bevy_ratatui::{
+    event::{FocusEvent as TerminalFocusEvent, KeyEvent, MouseEvent},
+    RatatuiPlugins,
+};
 use bevy_ratatui_camera::RatatuiCameraPlugin;
+use ratatui::{backend::TestBackend, Terminal};
 
+mod algae;
+mod ambience;
 mod assets;
+mod audio;
 mod bubbles;
 mod camera;
+mod capture;
+pub mod cli;
+mod colors;
+pub mod commits;
+mod config;
+#[cfg(unix)]
+pub mod control;
 mod creatures;
 mod draw;
+mod filter;
+mod focus;
+mod framerate;
 mod general;
+mod graphics;
 mod input;
+mod keymap;
 mod pellets;
+mod render;
+mod simulation;
+mod stats;
 mod tank;
+mod temperature;
+mod toast;
+mod water;
 
 pub struct AppPlugin;
 
 impl Plugin for AppPlugin {
     fn build(&self, app: &mut App) {
+        let record = app
+            .world()
+            .get_resource::<cli::Options>()
+            .and_then(|options| options.record.clone());
+
         app.add_plugins((
             DefaultPlugins
                 .set(ImagePlugin::default_nearest())
                 .disable::<WinitPlugin>()
                 .disable::<LogPlugin>(),
-            ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1. / 90.)),
+            // frames are paced by the framerate plugin instead.
+            ScheduleRunnerPlugin::run_loop(Duration::ZERO),
             FrameTimeDiagnosticsPlugin {
                 smoothing_factor: 1.0,
                 ..default()
             },
-            RatatuiPlugins {
-                enable_mouse_capture: true,
-                ..default()
-            },
             RatatuiCameraPlugin,
             AtmospherePlugin,
             HanabiPlugin,
         ))
         .init_resource::<Flags>();
 
+        // recordings draw into a pretend terminal, leaving the real one alone.
+        if let Some(record) = record {
+            let (width, height) = record.size;
+            let terminal = Terminal::new(TestBackend::new(width, height))
+                .expect("a test backend cannot fail to start");
+            app.insert_resource(capture::HeadlessTerminal(terminal))
+                .add_event::<KeyEvent>()
+                .add_event::<MouseEvent>()
+                .add_event::<TerminalFocusEvent>();
+        } else {
+            app.add_plugins(RatatuiPlugins {
+                enable_mouse_capture: true,
+                ..default()
+            });
+        }
+
+        // sections of the config file are read as the other plugins are built.
+        app.add_plugins(config::plugin);
+
         app.add_plugins((
+            algae::plugin,
+            ambience::plugin,
             assets::plugin,
             bubbles::plugin,
             camera::plugin,
+            capture::plugin,
+            colors::plugin,
+            commits::plugin,
             draw::plugin,
             creatures::plugin,
+            focus::plugin,
+            framerate::plugin,
             general::plugin,
             input::plugin,
+            keymap::plugin,
+        ))
+        .add_plugins((
+            audio::plugin,
+            filter::plugin,
+            graphics::plugin,
             pellets::plugin,
+            render::plugin,
+            simulation::plugin,
+            stats::plugin,
             tank::plugin,
-        ));
+            temperature::plugin,
+            toast::plugin,
+            water::plugin,
+        ))
+        .init_resource::<cli::Options>();
+
+        #[cfg(unix)]
+        app.add_plugins(control::plugin);
     }
 }
 
 #[derive(Resource, Default)]
 pub struct Flags {
     debug: bool,
+    help: bool,
     muted: bool,
+    stats: bool,
     msg: String,
 }
@@ -1,6 +1,77 @@
 use bevy::prelude::*;
-use lifecycler::AppPlugin;
+use lifecycler::{
+    cli::{self, Command, USAGE},
+    commits::{self, HookInstall},
+    AppPlugin,
+};
 
 fn main() -> AppExit {
-    App::new().add_plugins(AppPlugin).run()
+    match cli::parse(std::env::args().skip(1)) {
+        Ok(Command::Run(options)) => App::new()
+            .insert_resource(options)
+            .add_plugins(AppPlugin)
+            .run(),
+        Ok(Command::Ctl { socket, words }) => ctl(socket, words),
+        Ok(Command::HookInstall { repo }) => match commits::install_hook(&repo) {
+            Ok(installed) => {
+                match installed {
+                    HookInstall::Created(hook) => println!("created {}", hook.display()),
+                    HookInstall::Extended(hook) => println!(
+                        "added a line logging commits to {}, right after its #! line",
+                        hook.display()
+                    ),
+                }
+                println!("run lifecycler --watch {} to feed the fish", repo.display());
+                AppExit::Success
+            }
+            Err(error) => {
+                eprintln!("{error}");
+                AppExit::error()
+            }
+        },
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            AppExit::Success
+        }
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            AppExit::error()
+        }
+    }
+}
+
+#[cfg(unix)]
+fn ctl(socket: Option<std::path::PathBuf>, words: Vec<String>) -> AppExit {
+    use lifecycler::control::{self, ControlCommand};
+
+    let request = if words[0].starts_with('{') {
+        Ok(words.join(" "))
+    } else {
+        ControlCommand::from_words(&words)
+            .and_then(|command| serde_json::to_string(&command).map_err(|e| e.to_string()))
+    };
+
+    let socket = socket.unwrap_or_else(cli::default_socket_path);
+
+    let result = request.and_then(|request| {
+        control::send(&socket, &request)
+            .map_err(|error| format!("could not reach {}: {error}", socket.display()))
+    });
+
+    match result {
+        Ok(response) => {
+            println!("{response}");
+            AppExit::Success
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            AppExit::error()
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn ctl(_socket: Option<std::path::PathBuf>, _words: Vec<String>) -> AppExit {
+    eprintln!("the control server is only available on unix");
+    AppExit::error()
 }
@@ -5,24 +5,51 @@ use rand_chacha::{
     ChaCha8Rng,
 };
 
-use crate::{general::play_sfx, Flags};
+use crate::{
+    audio::{Sfx, SfxEvent},
+    filter::Current,
+    general::AttemptDespawn,
+    water::RippleEvent,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Startup, (setup_pellets_system, setup_sfx_system))
+    app.add_systems(Startup, setup_pellets_system)
+        .add_systems(Update, create_pellets_system)
         .add_systems(
-            Update,
+            FixedUpdate,
             (
-                create_pellets_system,
                 move_pellets_system,
                 perish_perishables_system,
+                eat_pellets_system,
             ),
         )
         .init_resource::<PelletThreshold>()
-        .add_event::<PelletEvent>();
+        .init_resource::<PelletTally>()
+        .add_event::<PelletEvent>()
+        .add_event::<PelletBiteEvent>()
+        .add_event::<PelletEatenEvent>();
 }
 
+pub const PELLET_BITES: u32 = 3;
+/// Height just under the water line where dropped pellets start sinking from.
+pub const PELLET_SURFACE_HEIGHT: f32 = 1.6;
+
 #[derive(Component)]
-pub struct Pellet;
+pub struct Pellet {
+    /// Bites left before the pellet is gone.
+    pub bites: u32,
+    /// Number of creatures currently heading for this pellet.
+    pub claimants: u32,
+}
+
+impl Default for Pellet {
+    fn default() -> Self {
+        Self {
+            bites: PELLET_BITES,
+            claimants: 0,
+        }
+    }
+}
 
 #[derive(Component, Deref)]
 pub struct PelletFalling(Vec3);
@@ -42,11 +69,24 @@ pub struct PelletThreshold(u32);
 #[derive(Component, Deref, DerefMut)]
 pub struct Perishable(Timer);
 
+/// Running totals of what became of the pellets dispensed into the tank.
+#[derive(Resource, Default, Clone, Copy)]
+pub struct PelletTally {
+    pub dispensed: u32,
+    pub eaten: u32,
+    pub rotted: u32,
+}
+
+/// Drops a pellet, colored by an index into the pellet colors or otherwise at random.
 #[derive(Event, Deref)]
-pub struct PelletEvent(pub Transform);
+pub struct PelletEvent(#[deref] pub Transform, pub Option<usize>);
 
-#[derive(Resource, Deref)]
-pub struct PelletSound(Handle<AudioSource>);
+#[derive(Event, Deref)]
+pub struct PelletBiteEvent(pub Entity);
+
+/// Sent when a creature finishes off a pellet, whether with its last bite or in one go.
+#[derive(Event, Deref)]
+pub struct PelletEatenEvent(pub Entity);
 
 fn setup_pellets_system(
     mut commands: Commands,
@@ -73,46 +113,62 @@ fn setup_pellets_system(
     commands.insert_resource(PelletRng(seeded_rng));
 }
 
-fn setup_sfx_system(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.insert_resource(PelletSound(
-        asset_server.load("embedded://lifecycler/../assets/bubble.ogg"),
-    ));
-}
-
 fn create_pellets_system(
     mut commands: Commands,
-    flags: Res<Flags>,
     mut pellet_events: EventReader<PelletEvent>,
     mut pellet_rng: ResMut<PelletRng>,
     pellet_mesh: Res<PelletMesh>,
     pellet_materials: Res<PelletMaterials>,
-    pellet_sound: Res<PelletSound>,
+    mut sfx_events: EventWriter<SfxEvent>,
+    mut ripple_events: EventWriter<RippleEvent>,
+    mut tally: ResMut<PelletTally>,
 ) {
-    for mouse_position in pellet_events.read() {
+    for pellet_event in pellet_events.read() {
+        tally.dispensed += 1;
+
         let fall_target = Vec3::new(
-            mouse_position.translation.x.clamp(-1.75, 1.75),
+            pellet_event.translation.x.clamp(-1.75, 1.75),
             -1.7,
             pellet_rng.next_u32() as f32 / u32::MAX as f32 * 0.75 - 0.25,
         );
 
-        play_sfx(&mut commands, &pellet_sound, &flags);
+        let material = match pellet_event.1 {
+            Some(index) => pellet_materials[index % pellet_materials.len()].clone(),
+            None => pellet_materials.choose(&mut pellet_rng.0).unwrap().clone(),
+        };
+
+        // food always goes in from the top, wherever it was aimed.
+        let entry = pellet_event
+            .translation
+            .with_x(fall_target.x)
+            .with_y(PELLET_SURFACE_HEIGHT);
+
+        sfx_events.write(SfxEvent(Sfx::Pellet, Some(fall_target.x)));
+        ripple_events.write(RippleEvent(entry.xz()));
 
         commands.spawn((
-            Pellet,
+            Pellet::default(),
             PelletFalling(fall_target),
-            **mouse_position,
+            pellet_event.with_translation(entry),
             Mesh3d(pellet_mesh.clone()),
-            MeshMaterial3d(pellet_materials.choose(&mut pellet_rng.0).unwrap().clone()),
+            MeshMaterial3d(material),
         ));
     }
 }
 
 fn move_pellets_system(
     mut commands: Commands,
-    mut pellets: Query<(Entity, &mut Transform, &PelletFalling)>,
+    mut pellets: Query<(Entity, &mut Transform, &mut PelletFalling)>,
     time: Res<Time>,
+    current: Res<Current>,
 ) {
-    for (id, mut pellet_transform, PelletFalling(fall_target)) in &mut pellets {
+    for (id, mut pellet_transform, mut falling) in &mut pellets {
+        // the current carries sinking food along, so it lands further downstream.
+        let drift = current.at(pellet_transform.translation) * time.delta_secs();
+        pellet_transform.translation += drift;
+        falling.0.x = (falling.0.x + drift.x).clamp(-1.75, 1.75);
+        let fall_target = &falling.0;
+
         pellet_transform.translation = pellet_transform
             .translation
             .move_towards(*fall_target, time.delta_secs() * 0.3);
@@ -132,13 +188,35 @@ fn move_pellets_system(
 fn perish_perishables_system(
     mut commands: Commands,
     time: Res<Time>,
-    mut perishables: Query<(Entity, &mut Perishable)>,
+    mut perishables: Query<(Entity, &mut Perishable), Without<AttemptDespawn>>,
+    mut tally: ResMut<PelletTally>,
 ) {
     let delta = time.delta();
     for (id, mut timer) in &mut perishables {
         timer.tick(delta);
         if timer.finished() {
+            tally.rotted += 1;
             commands.entity(id).despawn();
         }
     }
 }
+
+fn eat_pellets_system(
+    mut commands: Commands,
+    mut eaten_events: EventReader<PelletEatenEvent>,
+    pellets: Query<(), (With<Pellet>, Without<AttemptDespawn>)>,
+    mut tally: ResMut<PelletTally>,
+) {
+    let mut eaten = Vec::new();
+
+    for pellet_id in eaten_events.read() {
+        // two creatures can finish off the same pellet before it is gone.
+        if !pellets.contains(**pellet_id) || eaten.contains(&**pellet_id) {
+            continue;
+        }
+
+        eaten.push(**pellet_id);
+        tally.eaten += 1;
+        commands.entity(**pellet_id).try_insert(AttemptDespawn);
+    }
+}
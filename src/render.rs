@@ -0,0 +1,203 @@
+use std::{fmt, str::FromStr};
+
+use bevy::prelude::*;
+use bevy_ratatui_camera::{
+    LuminanceConfig, RatatuiCamera, RatatuiCameraEdgeDetection, RatatuiCameraStrategy,
+};
+
+use crate::{
+    cli::Options,
+    colors::ColorDepth,
+    graphics::{ImageOutput, ImageProtocol},
+    toast::ToastEvent,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, choose_render_mode_system)
+        .add_systems(
+            Update,
+            (
+                cycle_render_mode_system,
+                apply_render_mode_system.run_if(resource_exists_and_changed::<RenderMode>),
+            )
+                .chain(),
+        )
+        .add_event::<CycleRenderEvent>();
+}
+
+/// How the camera image is turned into terminal cells.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum RenderMode {
+    /// Pick whichever suits the terminal.
+    #[default]
+    Auto,
+    /// Two pixels per cell, which needs 24-bit color to look right.
+    HalfBlocks,
+    Braille,
+    /// Characters chosen by brightness.
+    Ascii,
+    /// Outlines only.
+    Edges,
+    /// Sixel or kitty images.
+    Graphics,
+}
+
+impl RenderMode {
+    /// The modes the render key cycles through.
+    const CYCLE: [Self; 4] = [Self::HalfBlocks, Self::Braille, Self::Ascii, Self::Edges];
+
+    /// Settles on a mode that the terminal can actually manage. Images are only drawn when
+    /// asked for, since they send the whole tank to the terminal every frame.
+    fn resolve(self, depth: ColorDepth, protocol: Option<ImageProtocol>) -> (Self, Option<String>) {
+        let fallback = Self::detect(depth);
+
+        match self {
+            Self::Auto => (fallback, None),
+            Self::Graphics if protocol.is_none() => (
+                fallback,
+                Some(format!(
+                    "no sixel or kitty graphics found in this terminal, using {fallback}"
+                )),
+            ),
+            mode => (mode, None),
+        }
+    }
+
+    fn detect(depth: ColorDepth) -> Self {
+        let term = std::env::var("TERM").unwrap_or_default();
+
+        if term == "dumb" {
+            return Self::Ascii;
+        }
+
+        // with only the basic colors, shapes drawn with characters survive better than pixels.
+        if depth == ColorDepth::Ansi16 {
+            Self::Ascii
+        } else {
+            Self::HalfBlocks
+        }
+    }
+}
+
+impl FromStr for RenderMode {
+    type Err = String;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "auto" => Ok(Self::Auto),
+            "half-blocks" => Ok(Self::HalfBlocks),
+            "braille" => Ok(Self::Braille),
+            "ascii" => Ok(Self::Ascii),
+            "edges" => Ok(Self::Edges),
+            "graphics" => Ok(Self::Graphics),
+            _ => Err(format!("unknown render mode \"{mode}\"")),
+        }
+    }
+}
+
+impl fmt::Display for RenderMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Auto => "auto",
+            Self::HalfBlocks => "half-blocks",
+            Self::Braille => "braille",
+            Self::Ascii => "ascii",
+            Self::Edges => "edges",
+            Self::Graphics => "graphics",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Event, Default)]
+pub struct CycleRenderEvent;
+
+fn choose_render_mode_system(
+    mut commands: Commands,
+    options: Res<Options>,
+    mut output: ResMut<ImageOutput>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    // recordings draw into a pretend terminal, which can only hold characters.
+    output.protocol = if options.record.is_none() {
+        ImageProtocol::detect()
+    } else {
+        None
+    };
+
+    let (mode, problem) = options
+        .render_mode
+        .resolve(ColorDepth::resolve(options.color_depth), output.protocol);
+
+    if let Some(problem) = problem {
+        toast_events.write(ToastEvent(problem));
+    }
+
+    commands.insert_resource(mode);
+}
+
+fn cycle_render_mode_system(
+    mut cycle_events: EventReader<CycleRenderEvent>,
+    mode: Option<ResMut<RenderMode>>,
+    output: Res<ImageOutput>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    let Some(mut mode) = mode else {
+        return;
+    };
+
+    let mut cycle = RenderMode::CYCLE.to_vec();
+    if output.protocol.is_some() {
+        cycle.push(RenderMode::Graphics);
+    }
+
+    for _ in cycle_events.read() {
+        let index = cycle
+            .iter()
+            .position(|cycled| *cycled == *mode)
+            .map_or(0, |index| (index + 1) % cycle.len());
+
+        *mode = cycle[index];
+        toast_events.write(ToastEvent(format!("rendering with {}", *mode)));
+    }
+}
+
+fn apply_render_mode_system(
+    mut commands: Commands,
+    mode: Res<RenderMode>,
+    mut output: ResMut<ImageOutput>,
+    camera: Single<Entity, With<RatatuiCamera>>,
+) {
+    let mut camera = commands.entity(*camera);
+    camera.remove::<RatatuiCameraEdgeDetection>();
+    let active = *mode == RenderMode::Graphics;
+    if output.active != active {
+        output.active = active;
+    }
+
+    match *mode {
+        RenderMode::Braille => {
+            camera.insert(RatatuiCameraStrategy::Luminance(LuminanceConfig {
+                luminance_characters: LuminanceConfig::LUMINANCE_CHARACTERS_BRAILLE.into(),
+                ..default()
+            }));
+        }
+        RenderMode::Ascii => {
+            camera.insert(RatatuiCameraStrategy::Luminance(LuminanceConfig::default()));
+        }
+        RenderMode::Edges => {
+            camera.insert((
+                RatatuiCameraStrategy::Luminance(LuminanceConfig {
+                    luminance_characters: vec![' '],
+                    ..default()
+                }),
+                RatatuiCameraEdgeDetection::default(),
+            ));
+        }
+        // images are drawn straight from the camera, whichever way its cells would have been.
+        RenderMode::HalfBlocks | RenderMode::Auto | RenderMode::Graphics => {
+            camera.insert(RatatuiCameraStrategy::default());
+        }
+    }
+}
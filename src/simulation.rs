@@ -0,0 +1,173 @@
+use bevy::{app::FixedMain, prelude::*};
+
+use crate::{
+    creatures::{behavior::CreatureBehavior, lifecycle::FishSkeleton},
+    pellets::Pellet,
+    toast::ToastEvent,
+};
+
+/// Simulation speeds the speed keys step through, with zero meaning paused.
+const SPEEDS: [f32; 4] = [0., 1., 2., 10.];
+const NORMAL_SPEED: usize = 1;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        RunFixedMainLoop,
+        (
+            (restore_simulated_transforms_system, step_simulation_system)
+                .chain()
+                .in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+            interpolate_transforms_system.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+        ),
+    )
+    .add_systems(FixedLast, record_simulated_transforms_system)
+    .add_systems(Update, change_speed_system)
+    .register_required_components::<CreatureBehavior, Interpolated>()
+    .register_required_components::<Pellet, Interpolated>()
+    .register_required_components::<FishSkeleton, Interpolated>()
+    .insert_resource(SimulationSpeed {
+        index: NORMAL_SPEED,
+        resume: NORMAL_SPEED,
+        steps: 0,
+    })
+    .add_event::<SpeedEvent>();
+}
+
+/// Which of the simulation speeds drives the virtual clock.
+#[derive(Resource)]
+pub struct SimulationSpeed {
+    index: usize,
+    /// The speed to go back to when unpausing.
+    resume: usize,
+    /// Single steps asked for while paused, taken before the next frame's fixed loop.
+    steps: u32,
+}
+
+impl SimulationSpeed {
+    pub fn multiplier(&self) -> f32 {
+        SPEEDS[self.index]
+    }
+
+    pub fn paused(&self) -> bool {
+        self.multiplier() == 0.
+    }
+}
+
+#[derive(Event)]
+pub enum SpeedEvent {
+    Faster,
+    Slower,
+    TogglePause,
+    /// Advances a paused simulation by a single fixed timestep.
+    Step,
+}
+
+/// Where the simulation last left an entity, and where it was the step before that, so it
+/// can be drawn smoothly in between however the frame rate and the fixed timestep line up.
+#[derive(Component, Default)]
+pub struct Interpolated {
+    previous: Option<Transform>,
+    current: Option<Transform>,
+}
+
+impl Interpolated {
+    /// How far the entity moved over the last fixed step.
+    pub fn displacement(&self) -> Vec3 {
+        match (self.previous, self.current) {
+            (Some(previous), Some(current)) => current.translation - previous.translation,
+            _ => Vec3::ZERO,
+        }
+    }
+}
+
+fn change_speed_system(
+    mut speed_events: EventReader<SpeedEvent>,
+    mut speed: ResMut<SimulationSpeed>,
+    mut time: ResMut<Time<Virtual>>,
+    mut toast_events: EventWriter<ToastEvent>,
+) {
+    for event in speed_events.read() {
+        let index = match event {
+            SpeedEvent::Faster => (speed.index + 1).min(SPEEDS.len() - 1),
+            SpeedEvent::Slower => speed.index.saturating_sub(1),
+            SpeedEvent::TogglePause if speed.paused() => speed.resume,
+            SpeedEvent::TogglePause => 0,
+            SpeedEvent::Step => {
+                if speed.paused() {
+                    speed.steps += 1;
+                }
+                continue;
+            }
+        };
+
+        if !speed.paused() {
+            speed.resume = speed.index;
+        }
+        speed.index = index;
+
+        if speed.paused() {
+            time.pause();
+            toast_events.write(ToastEvent("paused".into()));
+        } else {
+            time.unpause();
+            time.set_relative_speed(speed.multiplier());
+            toast_events.write(ToastEvent(format!(
+                "running at {}x speed",
+                speed.multiplier()
+            )));
+        }
+    }
+}
+
+/// Runs the fixed schedules once for each step asked for, the way the fixed main loop would
+/// have if a timestep had built up on the paused virtual clock.
+fn step_simulation_system(world: &mut World) {
+    let steps = std::mem::take(&mut world.resource_mut::<SimulationSpeed>().steps);
+    if steps == 0 {
+        return;
+    }
+
+    for _ in 0..steps {
+        let timestep = world.resource::<Time<Fixed>>().timestep();
+        world.resource_mut::<Time<Fixed>>().advance_by(timestep);
+        *world.resource_mut::<Time>() = world.resource::<Time<Fixed>>().as_generic();
+        world.run_schedule(FixedMain);
+    }
+
+    *world.resource_mut::<Time>() = world.resource::<Time<Virtual>>().as_generic();
+}
+
+/// Puts back the simulated transforms before stepping, undoing the last frame's blending.
+fn restore_simulated_transforms_system(mut query: Query<(&mut Transform, &Interpolated)>) {
+    for (mut transform, interpolated) in query.iter_mut() {
+        if let Some(current) = interpolated.current {
+            *transform = current;
+        }
+    }
+}
+
+fn record_simulated_transforms_system(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        interpolated.previous = Some(interpolated.current.unwrap_or(*transform));
+        interpolated.current = Some(*transform);
+    }
+}
+
+fn interpolate_transforms_system(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &Interpolated)>,
+) {
+    let fraction = fixed_time.overstep_fraction();
+
+    for (mut transform, interpolated) in query.iter_mut() {
+        let (Some(previous), Some(current)) = (interpolated.previous, interpolated.current) else {
+            continue;
+        };
+
+        *transform = Transform {
+            translation: previous.translation.lerp(current.translation, fraction),
+            rotation: previous.rotation.slerp(current.rotation, fraction),
+            scale: previous.scale.lerp(current.scale, fraction),
+        };
+    }
+}
@@ -0,0 +1,127 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+
+use crate::{
+    creatures::{
+        fish_systems::Fish,
+        lifecycle::{DeathRecord, FishMortality},
+        population::FishBirthEvent,
+        snail_systems::Snail,
+        starfish_systems::Starfish,
+    },
+    pellets::PelletTally,
+};
+
+const STATS_SAMPLE_INTERVAL_SECONDS: f32 = 1.;
+const STATS_HISTORY_INTERVAL_SECONDS: f32 = 30.;
+const STATS_HISTORY_LENGTH: usize = 360;
+const STATS_SAMPLES_PER_HOUR: usize = (3600. / STATS_HISTORY_INTERVAL_SECONDS) as usize;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            count_births_system,
+            sample_stats_system.run_if(on_timer(Duration::from_secs_f32(
+                STATS_SAMPLE_INTERVAL_SECONDS,
+            ))),
+            record_stats_history_system.run_if(on_timer(Duration::from_secs_f32(
+                STATS_HISTORY_INTERVAL_SECONDS,
+            ))),
+        )
+            .chain(),
+    )
+    .init_resource::<Stats>();
+}
+
+/// A snapshot of the tank, with births, deaths and pellets as running totals.
+#[derive(Clone, Copy, Default)]
+pub struct StatsSample {
+    pub fishes: usize,
+    pub snails: usize,
+    pub starfish: usize,
+    pub average_age: f32,
+    pub average_satiation: f32,
+    pub births: u32,
+    pub deaths: u32,
+    pub pellets: PelletTally,
+}
+
+#[derive(Resource, Default)]
+pub struct Stats {
+    births: u32,
+    pub current: StatsSample,
+    /// Samples taken every `STATS_HISTORY_INTERVAL_SECONDS`, oldest first.
+    pub history: VecDeque<StatsSample>,
+}
+
+impl Stats {
+    /// Births and deaths over the last hour, or over all of history if it is shorter.
+    pub fn hourly(&self) -> (u32, u32) {
+        let past = self
+            .history
+            .len()
+            .checked_sub(STATS_SAMPLES_PER_HOUR)
+            .and_then(|index| self.history.get(index))
+            .or(self.history.front())
+            .copied()
+            .unwrap_or_default();
+
+        (
+            self.current.births - past.births,
+            self.current.deaths - past.deaths,
+        )
+    }
+
+    /// The change in a running total between consecutive history samples.
+    pub fn deltas(&self, total: impl Fn(&StatsSample) -> u32) -> Vec<u64> {
+        self.history
+            .iter()
+            .zip(self.history.iter().skip(1))
+            .map(|(before, after)| total(after).saturating_sub(total(before)) as u64)
+            .collect()
+    }
+}
+
+fn count_births_system(mut birth_events: EventReader<FishBirthEvent>, mut stats: ResMut<Stats>) {
+    stats.births += birth_events.read().count() as u32;
+}
+
+fn sample_stats_system(
+    mut stats: ResMut<Stats>,
+    fishes: Query<&FishMortality, With<Fish>>,
+    snails: Query<(), With<Snail>>,
+    starfish: Query<(), With<Starfish>>,
+    death_record: Res<DeathRecord>,
+    pellet_tally: Res<PelletTally>,
+) {
+    let fish_count = fishes.iter().len();
+    let average = |value: fn(&FishMortality) -> u32| {
+        if fish_count == 0 {
+            0.
+        } else {
+            fishes.iter().map(value).sum::<u32>() as f32 / fish_count as f32
+        }
+    };
+
+    stats.current = StatsSample {
+        fishes: fish_count,
+        snails: snails.iter().len(),
+        starfish: starfish.iter().len(),
+        average_age: average(FishMortality::age),
+        average_satiation: average(FishMortality::satiation),
+        births: stats.births,
+        deaths: death_record.old_age + death_record.starvation + death_record.illness,
+        pellets: *pellet_tally,
+    };
+}
+
+fn record_stats_history_system(mut stats: ResMut<Stats>) {
+    let current = stats.current;
+    stats.history.push_back(current);
+
+    if stats.history.len() > STATS_HISTORY_LENGTH {
+        stats.history.pop_front();
+    }
+}
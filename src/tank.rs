@@ -2,6 +2,14 @@ use std::f32::consts::PI;
 
 use bevy::prelude::*;
 
+/// Rough centers of the coral and rock clusters placed in `setup_tank_system`.
+pub const DECORATION_ANCHORS: [Vec3; 4] = [
+    Vec3::new(-1.24, -1.5, 0.),
+    Vec3::new(1.2, -1.5, 0.),
+    Vec3::new(-1.6, -1.5, -0.4),
+    Vec3::new(1.6, -1.5, -0.3),
+];
+
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(Startup, setup_tank_system);
 }
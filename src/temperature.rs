@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+
+use crate::camera::Daytime;
+
+const AMBIENT_TEMPERATURE_DAYTIME: f32 = 24.;
+const AMBIENT_TEMPERATURE_NIGHTTIME: f32 = 20.;
+const AMBIENT_DRIFT_RATE: f32 = 0.01;
+const HEATER_POWER: f32 = 0.05;
+const HEATER_SETPOINT_DEFAULT: f32 = 25.;
+const HEATER_SETPOINT_STEP: f32 = 1.;
+const HEATER_SETPOINT_MIN: f32 = 16.;
+const HEATER_SETPOINT_MAX: f32 = 32.;
+const METABOLISM_REFERENCE: f32 = 25.;
+const STRESS_RANGE: f32 = 6.;
+
+pub const COMFORT_MIN: f32 = 22.;
+pub const COMFORT_MAX: f32 = 28.;
+pub const THERMOMETER_MIN: f32 = 14.;
+pub const THERMOMETER_MAX: f32 = 34.;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, setup_heater_system)
+        .add_systems(
+            Update,
+            (adjust_heater_system, water_temperature_system).chain(),
+        )
+        .init_resource::<WaterTemperature>()
+        .add_event::<HeaterEvent>();
+}
+
+/// Temperature of the tank water in degrees celsius.
+#[derive(Resource, Deref, DerefMut)]
+pub struct WaterTemperature(f32);
+
+impl Default for WaterTemperature {
+    fn default() -> Self {
+        Self(AMBIENT_TEMPERATURE_DAYTIME)
+    }
+}
+
+impl WaterTemperature {
+    /// Multiplier on how quickly cold-blooded creatures move and burn through food,
+    /// doubling for every ten degrees of warmth.
+    pub fn metabolism(&self) -> f32 {
+        2_f32.powf((**self - METABOLISM_REFERENCE) / 10.)
+    }
+
+    /// How far the water is outside the comfortable range, from zero to one.
+    pub fn stress(&self) -> f32 {
+        ((COMFORT_MIN - **self).max(**self - COMFORT_MAX) / STRESS_RANGE).clamp(0., 1.)
+    }
+}
+
+/// A thermostat-controlled heater stuck to the back glass.
+#[derive(Component)]
+pub struct Heater {
+    pub setpoint: f32,
+    pub heating: bool,
+}
+
+#[derive(Event)]
+pub enum HeaterEvent {
+    Raise,
+    Lower,
+}
+
+fn setup_heater_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Heater {
+            setpoint: HEATER_SETPOINT_DEFAULT,
+            heating: false,
+        },
+        Transform::from_xyz(1.55, -0.9, -0.38),
+        Mesh3d(meshes.add(Capsule3d::new(0.04, 0.8))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.3, 0.3, 0.32),
+            perceptual_roughness: 0.3,
+            ..default()
+        })),
+    ));
+}
+
+fn adjust_heater_system(
+    mut heater_events: EventReader<HeaterEvent>,
+    mut heater: Single<&mut Heater>,
+) {
+    for event in heater_events.read() {
+        let step = match event {
+            HeaterEvent::Raise => HEATER_SETPOINT_STEP,
+            HeaterEvent::Lower => -HEATER_SETPOINT_STEP,
+        };
+
+        heater.setpoint = (heater.setpoint + step).clamp(HEATER_SETPOINT_MIN, HEATER_SETPOINT_MAX);
+    }
+}
+
+fn water_temperature_system(
+    time: Res<Time>,
+    daytime: Res<Daytime>,
+    mut temperature: ResMut<WaterTemperature>,
+    heater: Single<(&mut Heater, &MeshMaterial3d<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let (mut heater, material) = heater.into_inner();
+
+    let ambient = if **daytime {
+        AMBIENT_TEMPERATURE_DAYTIME
+    } else {
+        AMBIENT_TEMPERATURE_NIGHTTIME
+    };
+
+    **temperature += (ambient - **temperature) * AMBIENT_DRIFT_RATE * time.delta_secs();
+
+    let heating = **temperature < heater.setpoint;
+    if heating {
+        **temperature += HEATER_POWER * time.delta_secs();
+    }
+
+    if heating != heater.heating {
+        heater.heating = heating;
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.emissive = if heating {
+                LinearRgba::rgb(2., 0.4, 0.1)
+            } else {
+                LinearRgba::BLACK
+            };
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    general::state_dir,
+    keymap::{Action, Keymap},
+};
+
+const TOAST_SECONDS: f32 = 5.;
+const TOAST_FADE_SECONDS: f32 = 2.;
+const FIRST_RUN_MARKER: &str = "first-run";
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, first_run_hint_system)
+        .add_systems(Update, toast_system)
+        .init_resource::<Toasts>()
+        .add_event::<ToastEvent>();
+}
+
+#[derive(Event, Deref)]
+pub struct ToastEvent(pub String);
+
+/// Short messages shown one after another along the bottom of the tank.
+#[derive(Resource, Default)]
+pub struct Toasts {
+    queue: VecDeque<String>,
+    current: Option<(String, Timer)>,
+}
+
+impl Toasts {
+    /// The message on screen, along with its opacity as it fades out.
+    pub fn current(&self) -> Option<(&str, f32)> {
+        self.current.as_ref().map(|(text, timer)| {
+            (
+                text.as_str(),
+                (timer.remaining_secs() / TOAST_FADE_SECONDS).min(1.),
+            )
+        })
+    }
+}
+
+fn first_run_hint_system(keymap: Res<Keymap>, mut toast_events: EventWriter<ToastEvent>) {
+    if !first_run() {
+        return;
+    }
+
+    if let Some(key) = keymap.keys_for(Action::ToggleHelp).next() {
+        toast_events.write(ToastEvent(format!("press {key} for help")));
+    }
+}
+
+/// Whether this is the first time lifecycler has run, leaving a marker behind so the next
+/// run is not. Without anywhere to keep the marker, every run counts as the first.
+fn first_run() -> bool {
+    let Some(dir) = state_dir() else {
+        return true;
+    };
+
+    let marker = dir.join(FIRST_RUN_MARKER);
+    if marker.exists() {
+        return false;
+    }
+
+    let _ = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&marker, ""));
+
+    true
+}
+
+fn toast_system(
+    time: Res<Time<Real>>,
+    mut toast_events: EventReader<ToastEvent>,
+    mut toasts: ResMut<Toasts>,
+) {
+    for toast in toast_events.read() {
+        toasts.queue.push_back(toast.0.clone());
+    }
+
+    if toasts
+        .current
+        .as_mut()
+        .is_some_and(|(_, timer)| timer.tick(time.delta()).finished())
+    {
+        toasts.current = None;
+    }
+
+    if toasts.current.is_none() {
+        toasts.current = toasts
+            .queue
+            .pop_front()
+            .map(|text| (text, Timer::from_seconds(TOAST_SECONDS, TimerMode::Once)));
+    }
+}
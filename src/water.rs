@@ -0,0 +1,255 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::camera::{Daylight, Daytime, LightLevel};
+
+/// Height of the water line, which pellets sink from and bubbles pop at.
+pub const WATER_SURFACE_HEIGHT: f32 = 1.7;
+
+const WATER_WIDTH: f32 = 3.6;
+const WATER_DEPTH: f32 = 1.;
+const WATER_SUBDIVISIONS: u32 = 48;
+const WAVE_AMPLITUDE: f32 = 0.012;
+const RIPPLE_AMPLITUDE: f32 = 0.05;
+const RIPPLE_SPEED: f32 = 0.8;
+const RIPPLE_WAVELENGTH: f32 = 0.12;
+const RIPPLE_DAMPING: f32 = 1.5;
+const RIPPLE_SECONDS: f32 = 3.;
+const CAUSTICS_HEIGHT: f32 = -1.66;
+const CAUSTICS_RESOLUTION: (u32, u32) = (144, 40);
+const CAUSTICS_STRENGTH_DAYTIME: f32 = 0.35;
+const CAUSTICS_STRENGTH_NIGHTTIME: f32 = 0.05;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, setup_water_system)
+        .add_systems(
+            Update,
+            (
+                start_ripples_system,
+                wave_system,
+                caustics_system,
+                caustics_light_system,
+            )
+                .chain(),
+        )
+        .init_resource::<Ripples>()
+        .add_event::<RippleEvent>();
+}
+
+#[derive(Component)]
+struct WaterSurface {
+    mesh: Handle<Mesh>,
+    /// Where each vertex sits on still water.
+    rest: Vec<[f32; 3]>,
+}
+
+#[derive(Component)]
+struct Caustics {
+    image: Handle<Image>,
+    material: Handle<StandardMaterial>,
+}
+
+/// Sets off a ripple at the given x and z on the surface.
+#[derive(Event, Deref)]
+pub struct RippleEvent(pub Vec2);
+
+struct Ripple {
+    center: Vec2,
+    age: f32,
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct Ripples(Vec<Ripple>);
+
+impl Ripples {
+    /// Height of the surface above its resting level, from the background swell and every
+    /// ripple whose front has passed this point.
+    fn height(&self, point: Vec2, seconds: f32) -> f32 {
+        let swell = (point.x * 3. + seconds * 1.2).sin() * WAVE_AMPLITUDE
+            + (point.y * 5. - seconds * 1.7 + point.x).sin() * WAVE_AMPLITUDE * 0.5;
+
+        let ripples: f32 = self
+            .iter()
+            .map(|ripple| {
+                let distance = point.distance(ripple.center);
+                let front = RIPPLE_SPEED * ripple.age;
+                if distance > front {
+                    return 0.;
+                }
+
+                let phase = (front - distance) / RIPPLE_WAVELENGTH * 2. * PI;
+                let falloff = (-ripple.age * RIPPLE_DAMPING).exp() / (1. + distance * 4.);
+                phase.sin() * RIPPLE_AMPLITUDE * falloff
+            })
+            .sum();
+
+        swell + ripples
+    }
+}
+
+fn setup_water_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let mesh = Plane3d::default()
+        .mesh()
+        .size(WATER_WIDTH, WATER_DEPTH)
+        .subdivisions(WATER_SUBDIVISIONS)
+        .build();
+    let rest = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .and_then(|positions| positions.as_float3())
+        .map(<[[f32; 3]]>::to_vec)
+        .unwrap_or_default();
+    let mesh = meshes.add(mesh);
+
+    commands.spawn((
+        WaterSurface {
+            mesh: mesh.clone(),
+            rest,
+        },
+        Mesh3d(mesh),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(0.5, 0.75, 0.9, 0.35),
+            perceptual_roughness: 0.1,
+            reflectance: 0.8,
+            alpha_mode: AlphaMode::Blend,
+            double_sided: true,
+            cull_mode: None,
+            ..default()
+        })),
+        Transform::from_xyz(0., WATER_SURFACE_HEIGHT, 0.),
+    ));
+
+    let (width, height) = CAUSTICS_RESOLUTION;
+    let image = images.add(Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    ));
+    let material = materials.add(StandardMaterial {
+        base_color_texture: Some(image.clone()),
+        unlit: true,
+        alpha_mode: AlphaMode::Add,
+        ..default()
+    });
+
+    commands.spawn((
+        Caustics {
+            image,
+            material: material.clone(),
+        },
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(WATER_WIDTH, WATER_DEPTH))),
+        MeshMaterial3d(material),
+        Transform::from_xyz(0., CAUSTICS_HEIGHT, 0.),
+    ));
+}
+
+fn start_ripples_system(
+    time: Res<Time>,
+    mut ripple_events: EventReader<RippleEvent>,
+    mut ripples: ResMut<Ripples>,
+) {
+    for ripple in ripples.iter_mut() {
+        ripple.age += time.delta_secs();
+    }
+    ripples.retain(|ripple| ripple.age < RIPPLE_SECONDS);
+
+    for center in ripple_events.read() {
+        ripples.push(Ripple {
+            center: **center,
+            age: 0.,
+        });
+    }
+}
+
+fn wave_system(
+    time: Res<Time>,
+    ripples: Res<Ripples>,
+    surface: Single<&WaterSurface>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let Some(mesh) = meshes.get_mut(&surface.mesh) else {
+        return;
+    };
+
+    let seconds = time.elapsed_secs();
+    let positions: Vec<[f32; 3]> = surface
+        .rest
+        .iter()
+        .map(|[x, _, z]| [*x, ripples.height(Vec2::new(*x, *z), seconds), *z])
+        .collect();
+
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.compute_normals();
+}
+
+/// Bright lines where light bent by the waves above comes together on the gravel, drifting
+/// with the position of the light.
+fn caustics_system(
+    time: Res<Time>,
+    caustics: Single<&Caustics>,
+    light: Single<&Transform, With<Daylight>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Some(data) = images
+        .get_mut(&caustics.image)
+        .and_then(|image| image.data.as_mut())
+    else {
+        return;
+    };
+
+    let seconds = time.elapsed_secs();
+    let (width, height) = CAUSTICS_RESOLUTION;
+    let offset = light.translation.x * 0.1;
+
+    for (index, pixel) in data.chunks_exact_mut(4).enumerate() {
+        let u = (index as u32 % width) as f32 / width as f32;
+        let v = (index as u32 / width) as f32 / height as f32;
+        let (x, z) = (u * WATER_WIDTH + offset, v * WATER_DEPTH);
+
+        let first = (x * 9. + seconds * 1.3 + (z * 7. - seconds).sin() * 1.5).sin();
+        let second = (z * 11. - seconds * 1.1 + (x * 5. + seconds * 0.7).sin() * 1.5).sin();
+        let brightness = (1. - (first + second).abs() * 0.5).powi(6);
+
+        let value = (brightness * 255.) as u8;
+        pixel.copy_from_slice(&[value, value, value, 255]);
+    }
+}
+
+fn caustics_light_system(
+    caustics: Single<&Caustics>,
+    light: Single<&PointLight, With<Daylight>>,
+    daytime: Res<Daytime>,
+    level: Res<LightLevel>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !daytime.is_changed() && !level.is_changed() {
+        return;
+    }
+
+    let Some(material) = materials.get_mut(&caustics.material) else {
+        return;
+    };
+
+    let strength = if **daytime {
+        CAUSTICS_STRENGTH_DAYTIME
+    } else {
+        CAUSTICS_STRENGTH_NIGHTTIME
+    };
+
+    material.base_color = (light.color.to_linear() * strength * **level).into();
+}